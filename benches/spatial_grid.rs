@@ -0,0 +1,99 @@
+use bevy::prelude::*;
+use hexen_havoc::art::Hitbox;
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use hexen_havoc::game::{difficulty_settings, hitboxes, spatial::{SpatialGrid, SpatialEntry, SpatialKind}};
+
+// Compares the spatial grid against checking every pair, for the number of enemies on late waves
+// Run with `cargo bench --bench spatial_grid`
+
+const WAVES: [u32; 4] = [20, 30, 35, 40];
+const SUMMONS: usize = 40;
+const PROJECTILES: usize = 400;
+const HITBOX: Hitbox = Hitbox::Capsule { radius: 19.0, height: 157.0, offset: Vec2::ZERO };
+const PROJECTILE_HITBOX: Hitbox = Hitbox::Circle { radius: 8.0, offset: Vec2::ZERO };
+const ITERATIONS: u32 = 20;
+
+fn main() {
+    println!("{:>5} {:>8} {:>14} {:>14} {:>14}", "wave", "enemies", "brute force", "grid build", "grid query");
+
+    for wave in WAVES {
+        let enemies = (difficulty_settings::STARTING_ENEMIES * difficulty_settings::ENEMIES_MULTIPLIER.powi(wave as i32)) as usize;
+        let entries = random_entries(enemies, wave);
+
+        let brute_force = time(|| brute_force(&entries));
+
+        let mut grid = SpatialGrid::default();
+        let build = time(|| {
+            grid.clear();
+            for entry in entries.iter() {
+                grid.insert(*entry);
+            }
+        });
+        let query = time(|| grid_query(&grid, &entries));
+
+        assert_eq!(brute_force.1, query.1, "grid found different collisions");
+        println!("{:>5} {:>8} {:>14?} {:>14?} {:>14?}", wave, enemies, brute_force.0, build.0, query.0);
+    }
+}
+
+// Enemies spread over the spawn area for the wave, with summons and projectiles over the screen
+fn random_entries(enemies: usize, wave: u32) -> Vec<SpatialEntry> {
+    let mut rng = StdRng::seed_from_u64(wave as u64);
+    let screen = Vec2::new(1920.0, 1080.0);
+    let spawn_radius = screen.y * difficulty_settings::SPAWN_RADIUS_BASE_MULTIPLIER + wave as f32 * difficulty_settings::SPAWN_RADIUS_SCALER;
+
+    let mut entries = Vec::new();
+    let mut entry = |kind: SpatialKind, min: Vec2, max: Vec2, hitbox: Hitbox, rng: &mut StdRng| {
+        entries.push(SpatialEntry {
+            entity: Entity::from_raw(entries.len() as u32),
+            kind,
+            position: Vec3::new(rng.gen_range(min.x..max.x), rng.gen_range(min.y..max.y), 0.0),
+            hitbox,
+        });
+    };
+
+    for _ in 0..enemies {
+        entry(SpatialKind::Enemy, Vec2::splat(-spawn_radius), screen + spawn_radius, HITBOX, &mut rng);
+    }
+    for _ in 0..SUMMONS {
+        entry(SpatialKind::Summon, Vec2::ZERO, screen, HITBOX, &mut rng);
+    }
+    for _ in 0..PROJECTILES {
+        entry(SpatialKind::SummonProjectile, Vec2::ZERO, screen, PROJECTILE_HITBOX, &mut rng);
+    }
+    entries
+}
+
+// Counts collisions between enemies and everything that can hit them, the same checks damage::deal_damage makes
+fn brute_force(entries: &[SpatialEntry]) -> usize {
+    let mut collisions = 0;
+    for a in entries.iter().filter(|entry| entry.kind != SpatialKind::Enemy) {
+        for b in entries.iter().filter(|entry| entry.kind == SpatialKind::Enemy) {
+            if hitboxes::are_hitboxes_colliding(&a.hitbox, &a.position, &b.hitbox, &b.position) {
+                collisions += 1;
+            }
+        }
+    }
+    collisions
+}
+
+fn grid_query(grid: &SpatialGrid, entries: &[SpatialEntry]) -> usize {
+    let mut collisions = 0;
+    for a in entries.iter().filter(|entry| entry.kind != SpatialKind::Enemy) {
+        collisions += grid.colliding(&a.hitbox, &a.position).filter(|b| b.kind == SpatialKind::Enemy).count();
+    }
+    collisions
+}
+
+// Average time taken by the function, and its last result
+fn time<T>(mut function: impl FnMut() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let mut result = black_box(function());
+    for _ in 1..ITERATIONS {
+        result = black_box(function());
+    }
+    (start.elapsed() / ITERATIONS, result)
+}
@@ -1,10 +1,11 @@
 use bevy::prelude::*;
-use std::time::Instant;
+use rand::Rng;
+use std::time::Duration;
 use std::f32::consts::PI;
 use crate::{AppState, art};
 use crate::game::{characters, GameState};
 
-pub const ANIMATION_START_OFFSET: [u32; 2] = [0, 300]; // Used so all the animations don't play at the same time, array defines a random offset in milliseconds
+pub const ANIMATION_START_OFFSET: [u64; 2] = [0, 300]; // Used so all the animations don't play at the same time, array defines a random offset in milliseconds
 
 #[derive(Component)]
 pub struct AnimationSprite {
@@ -24,12 +25,12 @@ impl Plugin for AnimationPlugin {
 // Animates all enemies and summons
 fn animate(
     mut animation_query: Query<(&AnimationSprite, &mut Transform, &mut TextureAtlas, &mut characters::Character)>,
+    time: Res<Time>,
 ) {
     for (animation_sprite, mut transform, mut texture_atlas, mut character) in animation_query.iter_mut() {
 
-
-        if character.last_animation_frame.elapsed().as_secs_f32() > 1.0 / art::ANIMATION_FPS {
-            character.last_animation_frame = Instant::now();
+        character.animation_timer.tick(time.delta());
+        if character.animation_timer.just_finished() {
             if texture_atlas.index != animation_sprite.frames - 1 {
                 texture_atlas.index += 1;
             } else {
@@ -50,4 +51,15 @@ fn animate(
             transform.rotation = Quat::from_rotation_y(rotation_2);
         }
     }
-}
\ No newline at end of file
+}
+
+// Timer for switching a character's animation frames, started with a random offset
+pub fn animation_timer(rng: &mut impl Rng) -> Timer {
+    let frame_time = Duration::from_secs_f32(1.0 / art::ANIMATION_FPS);
+    let offset = Duration::from_millis(rng.gen_range(ANIMATION_START_OFFSET[0]..ANIMATION_START_OFFSET[1]));
+
+    // Delaying the first frame by the offset is the same as starting the timer the offset before it finishes
+    let mut timer = Timer::new(frame_time, TimerMode::Repeating);
+    timer.set_elapsed(frame_time - Duration::from_nanos((offset.as_nanos() % frame_time.as_nanos()) as u64));
+    timer
+}
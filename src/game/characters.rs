@@ -1,197 +1,192 @@
 use bevy::prelude::*;
-use std::time::Instant;
+use serde::Deserialize;
 use crate::art;
 
 #[derive(Component, Debug)]
 pub struct Character {
     pub health: i32, // Current health
-    pub last_shot: Option<Instant>, // Last instant the character shot
-    pub last_damage: Instant, // Last instant the character was damaged
+    pub shot_timer: Option<Timer>, // Time until the character can shoot again, None for melee characters
+    pub invulnarability_timer: Timer, // The character can't be damaged until this has finished
     pub direction_vector: Vec3, // Most recent direction vector of the character
     pub type_index: usize, // Index in it's respective enemy or summoner type resource
     pub summon: bool,
-    pub position_index: usize, // Index in it's respective enemy or summoner position resource
-    pub last_animation_frame: Instant, // Last time the animation frame switched
+    pub animation_timer: Timer, // Switches the animation frame every time it finishes
 }
 
-#[derive(PartialEq, Eq, Copy, Clone)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Deserialize)]
 pub enum SummonType {
     Sentry,
     Melee,
     Ranged,
 }
 
-#[derive(Copy, Clone)]
+// Characters are defined in the content file, see game::content
+#[derive(Clone, Debug, Deserialize)]
 pub struct CharacterInfo {
+    pub name: String,
     pub max_health: i32,
     pub speed: f32,
 
+    #[serde(default)]
     pub summon_type: Option<SummonType>, // None of the character is an enemy
 
     // Fields related to projectiles have options
     // If no projectile information is provided melee is assumed
+    #[serde(default)]
     pub firing_rate: Option<f32>, // Time to wait in seconds between shooting
+    #[serde(default)]
     pub projectile_types_index: Option<usize>, // Index for the bullet in the projectile type resource
 
     pub contact_damange: i32,
     pub invulnarability_time: f32, // Invulnaribility time inbetween hits (in seconds)
 
+    #[serde(default)]
     pub collectible_type_required: Option<usize>, // Type of collectible the summon needs to spawn
+    #[serde(default)]
     pub collectible_ammount_required: Option<i32>, // Ammount of collectible the summon needs to spawn
+    #[serde(default)]
+    pub additional_cost: Vec<(usize, i32)>, // Other collectibles the summon needs to spawn, as (collectible type, ammount)
 
-    pub drop_collectible: Option<usize>, // Type of collectible the enemy drops
-    pub drop_collectible_sprite: Option<art::SpriteInformation>,
+    #[serde(default)]
+    pub sentry: Option<SentryInfo>, // Only used by sentry summons
+
+    #[serde(default)]
+    pub score: u32, // Points for killing the enemy
+
+    #[serde(default)]
+    pub drop_collectible: Option<usize>, // Type of collectible the enemy drops, the sprite comes from the collectible type resource
+
+    #[serde(default)]
+    pub behaviour: Behaviour, // How the enemy moves, see enemies::behaviours
+    #[serde(default)]
+    pub preferred_target: Option<SummonType>, // Summons of this type are hunted before others, see enemies::targeting
+    #[serde(default)]
+    pub boss: Option<BossInfo>, // Only used by bosses, which spawn alone on boss waves, see enemies::boss
 
     pub animation_information: art::AnimationSpriteInformation,
 }
 
-pub const ENEMY_TYPES: usize = 4;
-pub const SUMMON_TYPES: usize = 4;
-pub const COLLECTIBLE_TYPES: usize = 4;
+// Enemy movement, every behaviour is driven by it's own system in enemies::behaviours
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize)]
+pub enum Behaviour {
+    #[default]
+    Chase, // Walks straight at the player
+
+    // Stays this far from the player, backing away when the player gets closer
+    Kite {
+        preferred_distance: f32,
+    },
+
+    // Walks at the player until it is within range, then stops to wind up and lunges in a straight line
+    Charge {
+        range: f32,
+        wind_up: f32, // Seconds spent standing still before lunging
+        lunge_speed: f32,
+        lunge_time: f32, // Seconds the lunge lasts
+    },
+
+    // Circles around the player while closing in, so it arrives from the side
+    Flank {
+        spiral_angle: f32, // Degrees between walking straight at the player (0) and circling without getting closer (90)
+    },
+
+    // Follows other swarmers within this distance, while still heading towards the player
+    Swarm {
+        neighbour_radius: f32,
+    },
+}
+
+// Sentries are placed by the player and stay where they are placed
+// They run out once their lifetime is over or they have fired all their ammo
+#[derive(Clone, Debug, Deserialize)]
+pub struct SentryInfo {
+    pub range: f32, // Sentries only shoot at enemies within this distance
+    pub aim_time: f32, // Time in seconds to aim at a new target before shooting at it
+
+    #[serde(default)]
+    pub lifetime: Option<f32>, // Seconds of fighting before the sentry despawns
+    #[serde(default)]
+    pub ammo: Option<u32>, // Shots before the sentry despawns
+}
 
-// Enemies in vec are ordered in terms of difficulty
-#[derive(Resource)]
-pub struct EnemyTypes(pub [CharacterInfo; ENEMY_TYPES]);
+// Bosses are listed after the other enemies, they only spawn on boss waves
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossInfo {
+    pub scale: f32, // The sprite and hitbox are this many times larger than the animation information
+    pub drop_ammount: u32, // Number of drop_collectible the boss drops when it dies
+    pub phases: Vec<BossPhase>, // In order, each phase starts once the boss has lost enough health
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossPhase {
+    pub health: f32, // The phase starts once the boss is at or below this fraction of it's max health, the first phase starts at 1.0
+    pub attack_rate: f32, // Time to wait in seconds between attacks
+    pub attacks: Vec<BossAttack>, // Used one after the other, starting from the first again once they have all been used
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum BossAttack {
+
+    // Fires projectiles evenly spaced in every direction
+    Ring {
+        projectile_types_index: usize,
+        projectiles: u32,
+    },
+
+    // Spawns enemies around the boss
+    Summon {
+        enemy_type: usize,
+        count: u32,
+    },
+
+    // Lunges in a straight line at the boss's target
+    Charge {
+        lunge_speed: f32,
+        lunge_time: f32, // Seconds the lunge lasts
+    },
+}
+
+impl CharacterInfo {
+
+    // Every collectible the summon needs to spawn, as (collectible type, ammount)
+    pub fn cost(&self) -> impl Iterator<Item = (usize, i32)> + '_ {
+        self.collectible_type_required
+            .zip(self.collectible_ammount_required)
+            .into_iter()
+            .chain(self.additional_cost.iter().copied())
+    }
 
+    // True if the collectibles are enough to spawn the summon
+    pub fn affordable(&self, collectibles: &[i32]) -> bool {
+        self.cost().all(|(collectible_type, ammount)| collectibles[collectible_type] >= ammount)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CollectibleInfo {
+    pub name: String,
+    pub sprite_information: art::SpriteInformation,
+}
+
+// Enemies in vec are ordered in terms of difficulty, followed by the bosses
 #[derive(Resource)]
-pub struct SummonTypes(pub [CharacterInfo; SUMMON_TYPES]);
-
-pub struct CharacterPlugin;
-
-impl Plugin for CharacterPlugin {
-    fn build(&self, app: &mut App) {
-        app
-            .insert_resource(EnemyTypes(
-
-                    // Enemies ordered in terms of accending difficulty
-                    [
-                        CharacterInfo {
-                            max_health: 5,
-                            speed: 100.0,
-                            summon_type: None,
-                            firing_rate: Some(2.5),
-                            projectile_types_index: Some(0),
-                            contact_damange: 1,
-                            invulnarability_time: 0.0,
-                            collectible_type_required: None,
-                            collectible_ammount_required: None,
-                            drop_collectible: Some(0),
-                            drop_collectible_sprite: Some(art::BONE_COLLECTIBLE_SPRITE),
-                            animation_information: art::ENEMY_ANIMATION,
-                        },
-
-
-                        CharacterInfo {
-                            max_health: 7,
-                            speed: 120.0,
-                            summon_type: None,
-                            firing_rate: Some(2.3),
-                            projectile_types_index: Some(1),
-                            contact_damange: 2,
-                            invulnarability_time: 0.0,
-                            collectible_type_required: None,
-                            collectible_ammount_required: None,
-                            drop_collectible: Some(1),
-                            drop_collectible_sprite: Some(art::BLOOD_COLLECTIBLE_SPRITE),
-                            animation_information: art::ENEMY_ANIMATION,
-                        },
-
-                        CharacterInfo {
-                            max_health: 9,
-                            speed: 140.0,
-                            summon_type: None,
-                            firing_rate: Some(2.0),
-                            projectile_types_index: Some(2),
-                            contact_damange: 1,
-                            invulnarability_time: 0.0,
-                            collectible_type_required: None,
-                            collectible_ammount_required: None,
-                            drop_collectible: Some(2),
-                            drop_collectible_sprite: Some(art::CRYSTAL_COLLECTIBLE_1_SPRITE),
-                            animation_information: art::ENEMY_ANIMATION,
-                        },
-
-                        CharacterInfo {
-                            max_health: 11,
-                            speed: 170.0,
-                            summon_type: None,
-                            firing_rate: Some(1.8),
-                            projectile_types_index: Some(3),
-                            contact_damange: 1,
-                            invulnarability_time: 0.0,
-                            collectible_type_required: None,
-                            collectible_ammount_required: None,
-                            drop_collectible: Some(3),
-                            drop_collectible_sprite: Some(art::CRYSTAL_COLLECTIBLE_2_SPRITE),
-                            animation_information: art::ENEMY_ANIMATION,
-                        },
-                    ]
-                )
-            )
-
-            .insert_resource(SummonTypes(
-                [   
-                    CharacterInfo {
-                        max_health: 2,
-                        speed: 800.0,
-                        summon_type: Some(SummonType::Ranged),
-                        firing_rate: Some(1.0),
-                        projectile_types_index: Some(4),
-                        contact_damange: 1,
-                        invulnarability_time: 1.0,
-                        collectible_type_required: Some(0),
-                        collectible_ammount_required: Some(5),
-                        drop_collectible: None,
-                        drop_collectible_sprite: None,
-                        animation_information: art::EYE_SUMMON_ANIMATION,
-                    },
-
-                    CharacterInfo {
-                        max_health: 40,
-                        speed: 800.0,
-                        summon_type: Some(SummonType::Melee),
-                        firing_rate: None,
-                        projectile_types_index: None,
-                        contact_damange: 5,
-                        invulnarability_time: 0.0,
-                        collectible_type_required: Some(1),
-                        collectible_ammount_required: Some(4),
-                        drop_collectible: None,
-                        drop_collectible_sprite: None,
-                        animation_information: art::GHOST_SUMMON_ANIMATION,
-                    },
-
-                    CharacterInfo {
-                        max_health: 4,
-                        speed: 800.0,
-                        summon_type: Some(SummonType::Ranged),
-                        firing_rate: Some(0.8),
-                        projectile_types_index: Some(5),
-                        contact_damange: 1,
-                        invulnarability_time: 1.0,
-                        collectible_type_required: Some(2),
-                        collectible_ammount_required: Some(8),
-                        drop_collectible: None,
-                        drop_collectible_sprite: None,
-                        animation_information: art::FLAMEBALL_SUMMON_ANIMATION,
-                    },
-
-                    CharacterInfo {
-                        max_health: 80,
-                        speed: 800.0,
-                        summon_type: Some(SummonType::Melee),
-                        firing_rate: None,
-                        projectile_types_index: None,
-                        contact_damange: 10,
-                        invulnarability_time: 0.0,
-                        collectible_type_required: Some(3),
-                        collectible_ammount_required: Some(10),
-                        drop_collectible: None,
-                        drop_collectible_sprite: None,
-                        animation_information: art::DWARVE_SUMMON_ANIMATION,
-                    },
-                ]
-            )
-        );
+pub struct EnemyTypes(pub Vec<CharacterInfo>);
+
+impl EnemyTypes {
+
+    // Number of enemies that spawn in normal waves, bosses come after them
+    pub fn regular(&self) -> usize {
+        self.0.iter().take_while(|enemy_info| enemy_info.boss.is_none()).count()
     }
-}
\ No newline at end of file
+
+    // Type index of every boss
+    pub fn bosses(&self) -> Vec<usize> {
+        (self.regular()..self.0.len()).collect()
+    }
+}
+
+#[derive(Resource)]
+pub struct SummonTypes(pub Vec<CharacterInfo>);
+
+#[derive(Resource)]
+pub struct CollectibleTypes(pub Vec<CollectibleInfo>);
@@ -1,34 +1,41 @@
 use bevy::prelude::*;
 use crate::{AppState, art};
-use super::{GameState, player, hitboxes};
+use super::{GameState, ScoreIncrease, player, spatial};
 
 #[derive(Component)]
 pub struct Collectible {
     pub collectible_type: usize,
-    pub hitbox: Vec2,
+    pub hitbox: art::Hitbox,
 }
 pub struct CollectiblePlugin;
 
 impl Plugin for CollectiblePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Update, detect_collisions.run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+            .add_systems(FixedUpdate, detect_collisions.after(spatial::SpatialGridSystem).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
     }
 }
 
 // Detects collisions between collectible and player so that the players currency can be increased
 fn detect_collisions(
     mut commands: Commands,
-    collectible_query: Query<(&Transform, &Collectible, Entity)>,
-    mut player_query: Query<(&Transform, &mut player::Player)>
+    collectible_query: Query<&Collectible>,
+    mut player_query: Query<(&Transform, &mut player::Player)>,
+    spatial_grid: Res<spatial::SpatialGrid>,
+    mut score_increase_events: EventWriter<ScoreIncrease>,
 ) {
 
     let (player_transform, mut player) = player_query.get_single_mut().unwrap();
 
-    for (collectible_transform, collectible, entity) in collectible_query.iter() {
-        if hitboxes::are_hitboxes_colliding(&art::PLAYER_HITBOX, &player_transform.translation, &collectible.hitbox, &collectible_transform.translation) {
+    for entry in spatial_grid.colliding(&art::PLAYER_HITBOX, &player_transform.translation) {
+        if entry.kind != spatial::SpatialKind::Collectible {
+            continue;
+        }
+
+        if let Ok(collectible) = collectible_query.get(entry.entity) {
             player.collectibles[collectible.collectible_type] += 1;
-            commands.entity(entity).despawn();
+            commands.entity(entry.entity).despawn();
+            score_increase_events.send(ScoreIncrease::CollectiblePickedUp);
         }
     }
 }
@@ -0,0 +1,575 @@
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, LoadState, ReadAssetBytesError, io::{AssetReaderError, Reader}};
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use std::fmt;
+
+use crate::{AppState, art};
+use super::{characters, player, projectiles, rewards, waves};
+
+// Enemy, summon, projectile and collectible definitions loaded from a ron file through the asset server
+// Once loaded the content is inserted as the EnemyTypes, SummonTypes, ProjectileTypes, CollectibleTypes and PlayerAttackInfo resources
+// The optional wave script is loaded with the content, so it can be checked against it, and is inserted as the WaveScript resource
+#[derive(Asset, TypePath, Debug, Deserialize)]
+pub struct GameContent {
+    pub collectibles: Vec<characters::CollectibleInfo>,
+    pub projectiles: Vec<projectiles::ProjectileInfo>,
+    pub player_attack: player::PlayerAttackInfo,
+    pub enemies: Vec<characters::CharacterInfo>, // Ordered in terms of accending difficulty
+    pub summons: Vec<characters::CharacterInfo>,
+
+    #[serde(skip)]
+    pub waves: Vec<waves::ScriptedWave>, // Read from art::WAVES_PATH, empty if there is no wave script
+}
+
+#[derive(Resource)]
+struct ContentHandle(Handle<GameContent>);
+
+#[derive(Debug)]
+pub enum ContentError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    WavesRead(ReadAssetBytesError),
+    WavesParse(ron::error::SpannedError),
+
+    // A content entry has an invalid field
+    Invalid {
+        section: &'static str,
+        index: usize,
+        name: String,
+        field: &'static str,
+        reason: String,
+    },
+}
+
+impl fmt::Display for ContentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ContentError::Io(error) => write!(f, "could not read content file: {}", error),
+            ContentError::Parse(error) => write!(f, "could not parse content file: {}", error),
+            ContentError::WavesRead(error) => write!(f, "could not read wave script: {}", error),
+            ContentError::WavesParse(error) => write!(f, "could not parse wave script: {}", error),
+            ContentError::Invalid { section, index, name, field, reason } => {
+                write!(f, "{}[{}] \"{}\" has an invalid `{}`: {}", section, index, name, field, reason)
+            },
+        }
+    }
+}
+
+impl std::error::Error for ContentError {}
+
+impl From<std::io::Error> for ContentError {
+    fn from(error: std::io::Error) -> Self {
+        ContentError::Io(error)
+    }
+}
+
+impl From<ron::error::SpannedError> for ContentError {
+    fn from(error: ron::error::SpannedError) -> Self {
+        ContentError::Parse(error)
+    }
+}
+
+#[derive(Default)]
+struct ContentLoader;
+
+impl AssetLoader for ContentLoader {
+    type Asset = GameContent;
+    type Settings = ();
+    type Error = ContentError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<GameContent, ContentError>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+
+            let mut content: GameContent = ron::de::from_bytes(&bytes)?;
+
+            // Every wave is procedural if there isn't a wave script
+            // Reading it through the load context reloads the content when the script changes
+            content.waves = match load_context.read_asset_bytes(art::WAVES_PATH).await {
+                Ok(bytes) => ron::de::from_bytes(&bytes).map_err(ContentError::WavesParse)?,
+                Err(ReadAssetBytesError::AssetReaderError(AssetReaderError::NotFound(_))) => Vec::new(),
+                Err(error) => return Err(ContentError::WavesRead(error)),
+            };
+
+            content.validate()?;
+            content.scale_hitboxes();
+            Ok(content)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["content.ron"]
+    }
+}
+
+pub struct ContentPlugin;
+
+impl Plugin for ContentPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<GameContent>()
+            .init_asset_loader::<ContentLoader>()
+            .add_systems(Startup, load_content)
+            .add_systems(Update, insert_content)
+            .add_systems(OnEnter(AppState::GameSetup), reset_summon_types.in_set(super::GameSetupSystem))
+            .add_systems(Update, content_failed.run_if(in_state(AppState::Loading)));
+    }
+}
+
+fn load_content(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(ContentHandle(asset_server.load(art::CONTENT_PATH)));
+}
+
+// Inserts the content resources once the content file has loaded, and again if it is modified
+// Leaves AppState::Loading the first time the content is inserted
+fn insert_content(
+    mut commands: Commands,
+    mut asset_events: EventReader<AssetEvent<GameContent>>,
+    content_handle: Res<ContentHandle>,
+    content_assets: Res<Assets<GameContent>>,
+    app_state: Res<State<AppState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    for event in asset_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } if *id == content_handle.0.id() => {
+                let Some(content) = content_assets.get(*id) else {
+                    continue;
+                };
+
+                commands.insert_resource(characters::EnemyTypes(content.enemies.clone()));
+                commands.insert_resource(characters::SummonTypes(content.summons.clone()));
+                commands.insert_resource(characters::CollectibleTypes(content.collectibles.clone()));
+                commands.insert_resource(projectiles::ProjectileTypes(content.projectiles.clone()));
+                commands.insert_resource(content.player_attack.clone());
+                commands.insert_resource(waves::WaveScript(content.waves.clone()));
+
+                if app_state.get() == &AppState::Loading {
+                    next_app_state.set(AppState::MainMenu);
+                }
+            },
+            _ => (),
+        }
+    }
+}
+
+// Rewards change summon stats during a run, so every run starts from the summons in the content file
+fn reset_summon_types(
+    mut commands: Commands,
+    content_handle: Res<ContentHandle>,
+    content_assets: Res<Assets<GameContent>>,
+) {
+    if let Some(content) = content_assets.get(&content_handle.0) {
+        commands.insert_resource(characters::SummonTypes(content.summons.clone()));
+    }
+}
+
+// The game can't run without content, so exit if it fails to load
+// The asset server has already logged the reason
+fn content_failed(
+    content_handle: Res<ContentHandle>,
+    asset_server: Res<AssetServer>,
+    mut app_exit_events: EventWriter<bevy::app::AppExit>,
+) {
+    if asset_server.get_load_state(&content_handle.0) == Some(LoadState::Failed) {
+        error!("Game content could not be loaded from {}", art::CONTENT_PATH);
+        app_exit_events.send(bevy::app::AppExit);
+    }
+}
+
+impl GameContent {
+
+    // Checks indices between the content lists, and that each entry makes sense for what it is used as
+    pub fn validate(&self) -> Result<(), ContentError> {
+        if self.collectibles.is_empty() {
+            return Err(invalid("collectibles", 0, "", "collectibles", "at least one collectible type is required"));
+        }
+        if self.enemies.first().is_none_or(|enemy| enemy.boss.is_some()) {
+            return Err(invalid("enemies", 0, "", "enemies", "at least one enemy type that isn't a boss is required"));
+        }
+        if self.summons.is_empty() {
+            return Err(invalid("summons", 0, "", "summons", "at least one summon type is required"));
+        }
+
+        for (i, collectible) in self.collectibles.iter().enumerate() {
+            check_hitbox("collectibles", i, &collectible.name, "sprite_information.hitbox", &collectible.sprite_information.hitbox)?;
+        }
+
+        for (i, projectile) in self.projectiles.iter().enumerate() {
+            if projectile.speed <= 0.0 {
+                return Err(invalid("projectiles", i, &projectile.name, "speed", "must be greater than 0"));
+            }
+            if projectile.range.is_some_and(|range| range <= 0.0) {
+                return Err(invalid("projectiles", i, &projectile.name, "range", "must be greater than 0"));
+            }
+            check_hitbox("projectiles", i, &projectile.name, "sprite_information.hitbox", &projectile.sprite_information.hitbox)?;
+        }
+
+        self.validate_player_attack()?;
+
+        for (i, enemy) in self.enemies.iter().enumerate() {
+            self.validate_character("enemies", i, enemy, true)?;
+
+            if enemy.summon_type.is_some() {
+                return Err(invalid("enemies", i, &enemy.name, "summon_type", "enemies can't have a summon type"));
+            }
+            if enemy.sentry.is_some() {
+                return Err(invalid("enemies", i, &enemy.name, "sentry", "only sentry summons can have sentry settings"));
+            }
+            validate_behaviour(i, enemy)?;
+            match &enemy.boss {
+                Some(boss) => self.validate_boss(i, enemy, boss)?,
+                None if i > 0 && self.enemies[i - 1].boss.is_some() => return Err(invalid("enemies", i, &enemy.name, "boss", "bosses have to be listed after the other enemies")),
+                None => (),
+            }
+            match enemy.drop_collectible {
+                Some(collectible) => self.check_collectible_index("enemies", i, enemy, "drop_collectible", collectible)?,
+                None => return Err(invalid("enemies", i, &enemy.name, "drop_collectible", "enemies must drop a collectible")),
+            }
+        }
+
+        for (i, summon) in self.summons.iter().enumerate() {
+            self.validate_character("summons", i, summon, false)?;
+
+            if summon.summon_type.is_none() {
+                return Err(invalid("summons", i, &summon.name, "summon_type", "summons must have a summon type"));
+            }
+            if summon.behaviour != characters::Behaviour::Chase {
+                return Err(invalid("summons", i, &summon.name, "behaviour", "only enemies have behaviours"));
+            }
+            if summon.preferred_target.is_some() {
+                return Err(invalid("summons", i, &summon.name, "preferred_target", "only enemies pick targets"));
+            }
+            if summon.boss.is_some() {
+                return Err(invalid("summons", i, &summon.name, "boss", "only enemies can be bosses"));
+            }
+            if summon.score != 0 {
+                return Err(invalid("summons", i, &summon.name, "score", "only enemies give points"));
+            }
+            match summon.collectible_type_required {
+                Some(collectible) => self.check_collectible_index("summons", i, summon, "collectible_type_required", collectible)?,
+                None => return Err(invalid("summons", i, &summon.name, "collectible_type_required", "summons must require a collectible")),
+            }
+            match summon.collectible_ammount_required {
+                Some(ammount) if ammount > 0 => (),
+                _ => return Err(invalid("summons", i, &summon.name, "collectible_ammount_required", "must be greater than 0")),
+            }
+            for (collectible, ammount) in summon.additional_cost.iter() {
+                self.check_collectible_index("summons", i, summon, "additional_cost", *collectible)?;
+                if *ammount <= 0 {
+                    return Err(invalid("summons", i, &summon.name, "additional_cost", "ammounts must be greater than 0"));
+                }
+            }
+            validate_sentry(i, summon)?;
+        }
+
+        for (i, scripted_wave) in self.waves.iter().enumerate() {
+            self.validate_wave(i, scripted_wave)?;
+        }
+
+        Ok(())
+    }
+
+    // Checks fields that are shared by enemies and summons
+    fn validate_character(&self, section: &'static str, index: usize, character: &characters::CharacterInfo, enemy: bool) -> Result<(), ContentError> {
+        let name = &character.name;
+
+        if character.max_health <= 0 {
+            return Err(invalid(section, index, name, "max_health", "must be greater than 0"));
+        }
+        if character.speed < 0.0 {
+            return Err(invalid(section, index, name, "speed", "can't be negative"));
+        }
+        if character.animation_information.frames == 0 {
+            return Err(invalid(section, index, name, "animation_information.frames", "must be greater than 0"));
+        }
+        check_hitbox(section, index, name, "animation_information.hitbox", &character.animation_information.hitbox)?;
+
+        let frame_offsets = character.animation_information.frame_offsets.len();
+        if frame_offsets != 0 && frame_offsets != character.animation_information.frames {
+            return Err(invalid(section, index, name, "animation_information.frame_offsets", &format!("has {} offsets but the animation has {} frames", frame_offsets, character.animation_information.frames)));
+        }
+
+        // Ranged characters need both a firing rate and a projectile
+        match (character.firing_rate, character.projectile_types_index) {
+            (Some(firing_rate), Some(projectile_index)) => {
+                if firing_rate <= 0.0 {
+                    return Err(invalid(section, index, name, "firing_rate", "must be greater than 0"));
+                }
+
+                let Some(projectile) = self.projectiles.get(projectile_index) else {
+                    return Err(invalid(section, index, name, "projectile_types_index", &format!("{} is out of range, there are {} projectiles", projectile_index, self.projectiles.len())));
+                };
+
+                if projectile.enemy != enemy {
+                    return Err(invalid(section, index, name, "projectile_types_index", &format!("projectile \"{}\" belongs to the other side", projectile.name)));
+                }
+            },
+            (None, None) => (),
+            (Some(_), None) => return Err(invalid(section, index, name, "projectile_types_index", "required when firing_rate is set")),
+            (None, Some(_)) => return Err(invalid(section, index, name, "firing_rate", "required when projectile_types_index is set")),
+        }
+
+        Ok(())
+    }
+
+    // The player attack has to fire a friendly projectile
+    fn validate_player_attack(&self) -> Result<(), ContentError> {
+        let attack = &self.player_attack;
+
+        let Some(projectile) = self.projectiles.get(attack.projectile_types_index) else {
+            return Err(invalid("player_attack", 0, "", "projectile_types_index", &format!("{} is out of range, there are {} projectiles", attack.projectile_types_index, self.projectiles.len())));
+        };
+        if projectile.enemy {
+            return Err(invalid("player_attack", 0, "", "projectile_types_index", &format!("projectile \"{}\" belongs to the enemies", projectile.name)));
+        }
+        if attack.cooldown <= 0.0 {
+            return Err(invalid("player_attack", 0, "", "cooldown", "must be greater than 0"));
+        }
+
+        Ok(())
+    }
+
+    // Bosses start in a phase at full health, and every attack has to reference something that exists
+    fn validate_boss(&self, index: usize, enemy: &characters::CharacterInfo, boss: &characters::BossInfo) -> Result<(), ContentError> {
+        let name = &enemy.name;
+
+        if enemy.behaviour != characters::Behaviour::Chase {
+            return Err(invalid("enemies", index, name, "behaviour", "bosses walk at their target, they charge with their attacks instead"));
+        }
+        if boss.scale <= 0.0 {
+            return Err(invalid("enemies", index, name, "boss.scale", "must be greater than 0"));
+        }
+        if boss.drop_ammount == 0 {
+            return Err(invalid("enemies", index, name, "boss.drop_ammount", "must be greater than 0"));
+        }
+        if boss.phases.first().is_none_or(|phase| phase.health < 1.0) {
+            return Err(invalid("enemies", index, name, "boss.phases", "the first phase has to start at full health (1.0)"));
+        }
+
+        let mut last_health = f32::INFINITY;
+        for phase in boss.phases.iter() {
+            if phase.health <= 0.0 || phase.health >= last_health {
+                return Err(invalid("enemies", index, name, "boss.phases.health", "must be greater than 0 and less than the health of the phase before"));
+            }
+            last_health = phase.health;
+
+            if phase.attack_rate <= 0.0 {
+                return Err(invalid("enemies", index, name, "boss.phases.attack_rate", "must be greater than 0"));
+            }
+            if phase.attacks.is_empty() {
+                return Err(invalid("enemies", index, name, "boss.phases.attacks", "every phase needs at least one attack"));
+            }
+
+            for attack in phase.attacks.iter() {
+                match *attack {
+                    characters::BossAttack::Ring { projectile_types_index, projectiles } => {
+                        let Some(projectile) = self.projectiles.get(projectile_types_index) else {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.projectile_types_index", &format!("{} is out of range, there are {} projectiles", projectile_types_index, self.projectiles.len())));
+                        };
+                        if !projectile.enemy {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.projectile_types_index", &format!("projectile \"{}\" belongs to the other side", projectile.name)));
+                        }
+                        if projectiles == 0 {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.projectiles", "must be greater than 0"));
+                        }
+                    },
+                    characters::BossAttack::Summon { enemy_type, count } => {
+                        match self.enemies.get(enemy_type) {
+                            Some(summoned) if summoned.boss.is_some() => return Err(invalid("enemies", index, name, "boss.phases.attacks.enemy_type", "bosses can't summon other bosses")),
+                            Some(_) => (),
+                            None => return Err(invalid("enemies", index, name, "boss.phases.attacks.enemy_type", &format!("{} is out of range, there are {} enemies", enemy_type, self.enemies.len()))),
+                        }
+                        if count == 0 {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.count", "must be greater than 0"));
+                        }
+                    },
+                    characters::BossAttack::Charge { lunge_speed, lunge_time } => {
+                        if lunge_speed <= 0.0 {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.lunge_speed", "must be greater than 0"));
+                        }
+                        if lunge_time <= 0.0 {
+                            return Err(invalid("enemies", index, name, "boss.phases.attacks.lunge_time", "must be greater than 0"));
+                        }
+                    },
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Scripted waves have to spawn something, and their reward has to be one the player can be given
+    fn validate_wave(&self, index: usize, scripted_wave: &waves::ScriptedWave) -> Result<(), ContentError> {
+        if scripted_wave.groups.iter().all(|group| group.count == 0) {
+            return Err(invalid("waves", index, "", "groups", "every wave has to spawn at least one enemy"));
+        }
+
+        for group in scripted_wave.groups.iter() {
+            if group.enemy_type >= self.enemies.len() {
+                return Err(invalid("waves", index, "", "groups.enemy_type", &format!("{} is out of range, there are {} enemies", group.enemy_type, self.enemies.len())));
+            }
+            if group.delay < 0.0 {
+                return Err(invalid("waves", index, "", "groups.delay", "can't be negative"));
+            }
+        }
+
+        if scripted_wave.modifier.health <= 0.0 {
+            return Err(invalid("waves", index, "", "modifier.health", "must be greater than 0"));
+        }
+        if scripted_wave.modifier.firing_rate <= 0.0 {
+            return Err(invalid("waves", index, "", "modifier.firing_rate", "must be greater than 0"));
+        }
+
+        match scripted_wave.reward {
+            Some(rewards::Reward::Collectibles { collectible_type, ammount }) => {
+                if collectible_type >= self.collectibles.len() {
+                    return Err(invalid("waves", index, "", "reward.collectible_type", &format!("{} is out of range, there are {} collectibles", collectible_type, self.collectibles.len())));
+                }
+                if ammount <= 0 {
+                    return Err(invalid("waves", index, "", "reward.ammount", "must be greater than 0"));
+                }
+            },
+            Some(rewards::Reward::FreeSummon { summon_type }) => {
+                match self.summons.get(summon_type) {
+                    Some(summon) if summon.summon_type == Some(characters::SummonType::Sentry) => return Err(invalid("waves", index, "", "reward.summon_type", "sentries are placed by the player, so they can't be given away")),
+                    Some(_) => (),
+                    None => return Err(invalid("waves", index, "", "reward.summon_type", &format!("{} is out of range, there are {} summons", summon_type, self.summons.len()))),
+                }
+            },
+            Some(rewards::Reward::MaxHealth) | Some(rewards::Reward::FasterSummons) | None => (),
+        }
+
+        Ok(())
+    }
+
+    fn check_collectible_index(&self, section: &'static str, index: usize, character: &characters::CharacterInfo, field: &'static str, collectible: usize) -> Result<(), ContentError> {
+        if collectible >= self.collectibles.len() {
+            return Err(invalid(section, index, &character.name, field, &format!("{} is out of range, there are {} collectibles", collectible, self.collectibles.len())));
+        }
+        Ok(())
+    }
+
+    // Hitboxes are written in sprite pixels
+    fn scale_hitboxes(&mut self) {
+        for collectible in self.collectibles.iter_mut() {
+            collectible.sprite_information.hitbox = collectible.sprite_information.hitbox.scaled(art::SPRITE_SCALE);
+        }
+        for projectile in self.projectiles.iter_mut() {
+            projectile.sprite_information.hitbox = projectile.sprite_information.hitbox.scaled(art::SPRITE_SCALE);
+        }
+        for character in self.enemies.iter_mut().chain(self.summons.iter_mut()) {
+
+            // Bosses are drawn larger than their sprite
+            let scale = match &character.boss {
+                Some(boss) => art::SPRITE_SCALE * boss.scale,
+                None => art::SPRITE_SCALE,
+            };
+
+            let animation_information = &mut character.animation_information;
+            animation_information.hitbox = animation_information.hitbox.scaled(scale);
+            for offset in animation_information.frame_offsets.iter_mut() {
+                *offset *= scale;
+            }
+        }
+    }
+}
+
+// Behaviour distances and times have to be positive so every behaviour eventually reaches the player
+fn validate_behaviour(index: usize, enemy: &characters::CharacterInfo) -> Result<(), ContentError> {
+    let name = &enemy.name;
+    match enemy.behaviour {
+        characters::Behaviour::Chase => (),
+        characters::Behaviour::Kite { preferred_distance } => {
+            if preferred_distance <= 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.preferred_distance", "must be greater than 0"));
+            }
+        },
+        characters::Behaviour::Charge { range, wind_up, lunge_speed, lunge_time } => {
+            if range <= 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.range", "must be greater than 0"));
+            }
+            if wind_up < 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.wind_up", "can't be negative"));
+            }
+            if lunge_speed <= 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.lunge_speed", "must be greater than 0"));
+            }
+            if lunge_time <= 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.lunge_time", "must be greater than 0"));
+            }
+        },
+        characters::Behaviour::Flank { spiral_angle } => {
+            if !(0.0..90.0).contains(&spiral_angle) {
+                return Err(invalid("enemies", index, name, "behaviour.spiral_angle", "must be at least 0 and less than 90, at 90 the enemy never gets closer"));
+            }
+        },
+        characters::Behaviour::Swarm { neighbour_radius } => {
+            if neighbour_radius <= 0.0 {
+                return Err(invalid("enemies", index, name, "behaviour.neighbour_radius", "must be greater than 0"));
+            }
+        },
+    }
+
+    Ok(())
+}
+
+// Sentries have to be ranged, and have to run out eventually
+fn validate_sentry(index: usize, summon: &characters::CharacterInfo) -> Result<(), ContentError> {
+    let name = &summon.name;
+    let sentry = match (summon.summon_type, &summon.sentry) {
+        (Some(characters::SummonType::Sentry), Some(sentry)) => sentry,
+        (Some(characters::SummonType::Sentry), None) => return Err(invalid("summons", index, name, "sentry", "required for sentry summons")),
+        (_, Some(_)) => return Err(invalid("summons", index, name, "sentry", "only sentry summons can have sentry settings")),
+        (_, None) => return Ok(()),
+    };
+
+    if summon.firing_rate.is_none() {
+        return Err(invalid("summons", index, name, "firing_rate", "sentries must be ranged"));
+    }
+    if sentry.range <= 0.0 {
+        return Err(invalid("summons", index, name, "sentry.range", "must be greater than 0"));
+    }
+    if sentry.aim_time < 0.0 {
+        return Err(invalid("summons", index, name, "sentry.aim_time", "can't be negative"));
+    }
+    match (sentry.lifetime, sentry.ammo) {
+        (None, None) => return Err(invalid("summons", index, name, "sentry", "needs a lifetime or ammo")),
+        (Some(lifetime), _) if lifetime <= 0.0 => return Err(invalid("summons", index, name, "sentry.lifetime", "must be greater than 0")),
+        (_, Some(0)) => return Err(invalid("summons", index, name, "sentry.ammo", "must be greater than 0")),
+        _ => (),
+    }
+
+    Ok(())
+}
+
+// Hitboxes with no size can't be hit
+fn check_hitbox(section: &'static str, index: usize, name: &str, field: &'static str, hitbox: &art::Hitbox) -> Result<(), ContentError> {
+    let valid = match *hitbox {
+        art::Hitbox::Aabb { size, .. } => size.x > 0.0 && size.y > 0.0,
+        art::Hitbox::Circle { radius, .. } => radius > 0.0,
+        art::Hitbox::Capsule { radius, height, .. } => radius > 0.0 && height > 0.0,
+    };
+
+    if !valid {
+        return Err(invalid(section, index, name, field, "must have a size greater than 0"));
+    }
+    Ok(())
+}
+
+fn invalid(section: &'static str, index: usize, name: &str, field: &'static str, reason: &str) -> ContentError {
+    ContentError::Invalid {
+        section,
+        index,
+        name: name.to_string(),
+        field,
+        reason: reason.to_string(),
+    }
+}
@@ -1,154 +1,179 @@
+use std::f32::consts::TAU;
 use bevy::prelude::*;
-use std::time::Instant;
 use crate::{AppState, art};
-use super::{enemies, summons, characters, projectiles, hitboxes, WaveState, GameState, collectibles, GameComponent};
+use super::{enemies, summons, characters, projectiles, hitboxes, spatial, WaveState, GameState, collectibles, GameComponent, ScoreIncrease};
+
+const BOSS_DROP_RADIUS: f32 = 120.0; // Distance from a dead boss that it's drop is spread around
 
 pub struct DamagePlugin;
 
 impl Plugin for DamagePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Update, (deal_damage, despawn_characters).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)).before(enemies::move_enemies));
+            .add_systems(FixedUpdate, (deal_damage, despawn_characters).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)).after(spatial::SpatialGridSystem));
             
     }
 }
 
 
 // Despawn characters after they have taken too much damage
-// Spawn currency at enemy death location
+// Spawn currency at enemy death location, bosses drop a ring of currency around them
+#[allow(clippy::type_complexity)]
 fn despawn_characters(
     mut commands: Commands,
-    enemy_query: Query<(&characters::Character, Entity), (With<enemies::Enemy>, Without<summons::Summon>)>,
+    enemy_query: Query<(&Transform, &characters::Character, Entity), (With<enemies::Enemy>, Without<summons::Summon>)>,
     summon_query: Query<(&characters::Character, Entity), With<summons::Summon>>,
-    mut summon_positions: ResMut<summons::SummonPositions>,
-    mut enemy_positions: ResMut<enemies::EnemyPositions>,
     enemy_types: Res<characters::EnemyTypes>,
+    collectible_types: Res<characters::CollectibleTypes>,
     asset_server: Res<AssetServer>,
+    mut score_increase_events: EventWriter<ScoreIncrease>,
 ) {
     for (summon, entity) in summon_query.iter() {
         if summon.health <= 0 {
-            summon_positions.0[summon.position_index] = None;
             commands.entity(entity).despawn();
         }
     }
 
-    for (enemy, entity) in enemy_query.iter() {
+    for (enemy_transform, enemy, entity) in enemy_query.iter() {
         if enemy.health <= 0 {
-            let mut currency_spawn_position = enemy_positions.0[enemy.position_index].unwrap();
+            let mut currency_spawn_position = enemy_transform.translation;
             currency_spawn_position.z = -1.0;
-            let enemy_info = enemy_types.0[enemy.type_index];
+            let enemy_info = &enemy_types.0[enemy.type_index];
+            let collectible_type = enemy_info.drop_collectible.unwrap();
 
-            enemy_positions.0[enemy.position_index] = None;
             commands.entity(entity).despawn();
+            score_increase_events.send(ScoreIncrease::EnemyKilled { enemy_type: enemy.type_index });
 
             // When an enemy is despawned drop the appropriate ammount of currency
-            commands.spawn(
-                (
-                    collectibles::Collectible {
-                        collectible_type: enemy_info.drop_collectible.unwrap(),
-                        hitbox: enemy_info.drop_collectible_sprite.unwrap().hitbox,
-                    },
-                    GameComponent,
-                    SpriteBundle {
-                        transform: Transform {
-                            translation: currency_spawn_position,
-                            scale: Vec3::splat(art::SPRITE_SCALE),
-                            ..default()
-                        },
-                        texture: asset_server.load(enemy_info.drop_collectible_sprite.unwrap().sprite_path),
-                        ..default()
+            match &enemy_info.boss {
+                Some(boss_info) => {
+                    for i in 0..boss_info.drop_ammount {
+                        let angle = i as f32 * TAU / boss_info.drop_ammount as f32;
+                        let position = currency_spawn_position + Vec3::new(angle.cos(), angle.sin(), 0.0) * BOSS_DROP_RADIUS;
+                        spawn_collectible(&mut commands, collectible_type, position, &collectible_types, &asset_server);
                     }
-                )
-            );
+                },
+                None => spawn_collectible(&mut commands, collectible_type, currency_spawn_position, &collectible_types, &asset_server),
+            }
         }
     }
 }
 
+fn spawn_collectible(
+    commands: &mut Commands,
+    collectible_type: usize,
+    position: Vec3,
+    collectible_types: &characters::CollectibleTypes,
+    asset_server: &AssetServer,
+) {
+    let collectible_sprite = &collectible_types.0[collectible_type].sprite_information;
+
+    commands.spawn(
+        (
+            collectibles::Collectible {
+                collectible_type,
+                hitbox: collectible_sprite.hitbox,
+            },
+            GameComponent,
+            SpriteBundle {
+                transform: Transform {
+                    translation: position,
+                    scale: Vec3::splat(art::SPRITE_SCALE),
+                    ..default()
+                },
+                texture: asset_server.load(collectible_sprite.sprite_path.to_string()),
+                ..default()
+            }
+        )
+    );
+}
+
 // Deals projectile and contact damage to summons and enemies, respects invulnarability
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 fn deal_damage(
     mut commands: Commands,
-    mut enemy_query: Query<(&Transform, &mut characters::Character), (With<enemies::Enemy>, Without<summons::Summon>)>,
-    mut summon_query: Query<(&Transform, &mut characters::Character), With<summons::Summon>>,
+    mut enemy_query: Query<(&mut characters::Character, &mut enemies::Target), (With<enemies::Enemy>, Without<summons::Summon>)>,
+    mut summon_query: Query<(&Transform, &mut characters::Character, &TextureAtlas, Entity), With<summons::Summon>>,
     projectile_query: Query<(&Transform, &projectiles::Projectile, Entity)>,
     projectile_types: Res<projectiles::ProjectileTypes>,
     summon_types: Res<characters::SummonTypes>,
     enemy_types: Res<characters::EnemyTypes>,
+    spatial_grid: Res<spatial::SpatialGrid>,
+    time: Res<Time>,
 ) {
-    
+
+    // Count down invulnarability while the game is running
+    for mut character in enemy_query.iter_mut().map(|(character, _)| character).chain(summon_query.iter_mut().map(|(_, character, _, _)| character)) {
+        character.invulnarability_timer.tick(time.delta());
+    }
+
     for (projectile_transform, projectile, projectile_entity) in projectile_query.iter() {
         let projectile_info = &projectile_types.0[projectile.projectile_types_index];
+        let projectile_hitbox = &projectile_info.sprite_information.hitbox;
 
-        if projectile_info.enemy { // Deal damage from enemy projectiles to summons
-            for (summon_transform, mut summon) in summon_query.iter_mut() {
-                let summon_info = &summon_types.0[summon.type_index];
-
-                projectile_hit_character(
-                    &mut commands,
-                    projectile_entity, &projectile_info, &projectile_transform.translation,
-                    &summon_info, &mut summon, &summon_transform.translation
-                );
-            }
-        } else { // Deal damage from summon projectiles to enemies
-            for (enemy_transform, mut enemy) in enemy_query.iter_mut() {
-                let enemy_info = &enemy_types.0[enemy.type_index];
-
-                projectile_hit_character(
-                    &mut commands,
-                    projectile_entity, &projectile_info, &projectile_transform.translation,
-                    &enemy_info, &mut enemy, &enemy_transform.translation
-                );
+        for entry in spatial_grid.colliding(projectile_hitbox, &projectile_transform.translation) {
+            if projectile_info.enemy && entry.kind == spatial::SpatialKind::Summon { // Deal damage from enemy projectiles to summons
+                if let Ok((_, mut summon, _, _)) = summon_query.get_mut(entry.entity) {
+                    projectile_hit_character(&mut commands, projectile_entity, projectile_info, &mut summon);
+                }
+            } else if !projectile_info.enemy && entry.kind == spatial::SpatialKind::Enemy { // Deal damage from summon projectiles to enemies
+                if let Ok((mut enemy, mut target)) = enemy_query.get_mut(entry.entity) {
+                    if projectile_hit_character(&mut commands, projectile_entity, projectile_info, &mut enemy) {
+                        target.add_threat(projectile.shooter, projectile_info.damage);
+                    }
+                }
             }
         }
     }
 
     // Deal contact damage between summons and enemies
-    for (summon_transform, mut summon) in summon_query.iter_mut() {
+    for (summon_transform, mut summon, texture_atlas, summon_entity) in summon_query.iter_mut() {
         let summon_info = &summon_types.0[summon.type_index];
-        for (enemy_transform, mut enemy) in enemy_query.iter_mut() {
-            let enemy_info = &enemy_types.0[enemy.type_index];
+        let summon_hitbox = &hitboxes::animation_hitbox(&summon_info.animation_information, summon_transform, texture_atlas.index);
 
-            // Check if summon and enemy are colliding
-            if hitboxes::are_hitboxes_colliding(&summon_info.animation_information.hitbox, &summon_transform.translation, &enemy_info.animation_information.hitbox, &enemy_transform.translation) {
+        // Only enemies colliding with the summon are returned
+        for entry in spatial_grid.colliding(summon_hitbox, &summon_transform.translation).filter(|entry| entry.kind == spatial::SpatialKind::Enemy) {
+            let Ok((mut enemy, mut target)) = enemy_query.get_mut(entry.entity) else {
+                continue;
+            };
+            let enemy_info = &enemy_types.0[enemy.type_index];
 
-                // Deal damage to summons
-                if summon.last_damage.elapsed().as_secs_f32() > summon_info.invulnarability_time {
-                    summon.last_damage = Instant::now();
-                    summon.health -= enemy_info.contact_damange;
-                }
+            // Deal damage to summons
+            if summon.invulnarability_timer.finished() {
+                summon.invulnarability_timer.reset();
+                summon.health -= enemy_info.contact_damange;
+            }
 
-                // Deal damage to enemies
-                if enemy.last_damage.elapsed().as_secs_f32() > enemy_info.invulnarability_time {
-                    enemy.last_damage = Instant::now();
-                    enemy.health -= summon_info.contact_damange;
-                }
+            // Deal damage to enemies
+            if enemy.invulnarability_timer.finished() {
+                enemy.invulnarability_timer.reset();
+                enemy.health -= summon_info.contact_damange;
+                target.add_threat(summon_entity, summon_info.contact_damange);
             }
         }
     }
 }
 
-// Detect if a projectile hits a character
-// Damage character when this happens, respect invulnarability
+// Damage a character hit by a projectile, respect invulnarability
 // Despawn projectile
+// Returns true if the character was damaged
 fn projectile_hit_character(
     commands: &mut Commands,
     projectile_entity: Entity,
     projectile_info: &projectiles::ProjectileInfo,
-    projectile_position: &Vec3,
-    character_info: &characters::CharacterInfo,
     character: &mut characters::Character,
-    character_position: &Vec3,
-) {
-    if hitboxes::are_hitboxes_colliding(&projectile_info.sprite_information.hitbox, &projectile_position, &character_info.animation_information.hitbox, &character_position) {
+) -> bool {
 
-        // Despawn projectile if it isn't a piercing projectile
-        if !projectile_info.piercing {
-            commands.entity(projectile_entity).despawn();
-        }
-        
-        // Only damage character if it is outside of it's invulnarability time
-        if character.last_damage.elapsed().as_secs_f32() > character_info.invulnarability_time {
-            character.last_damage = Instant::now();
-            character.health -= projectile_info.damage;
-        }
+    // Despawn projectile if it isn't a piercing projectile
+    if !projectile_info.piercing {
+        commands.entity(projectile_entity).despawn();
     }
-}
\ No newline at end of file
+
+    // Only damage character if it is outside of it's invulnarability time
+    if character.invulnarability_timer.finished() {
+        character.invulnarability_timer.reset();
+        character.health -= projectile_info.damage;
+        return true;
+    }
+    false
+}
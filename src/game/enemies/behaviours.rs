@@ -0,0 +1,228 @@
+use rand::Rng;
+use bevy::prelude::*;
+use bevy::ecs::system::EntityCommands;
+use bevy::utils::HashMap;
+
+use crate::game::{characters::{self, Behaviour}, helpers, spatial, Arena};
+use super::{targeting::Target, Enemy};
+
+// Every behaviour has a component with it's state that is added when the enemy spawns, and a system that moves the enemies with that component
+// A new behaviour needs a variant in characters::Behaviour, a component added in add_behaviour, and a system added to EnemyPlugin
+
+const KITE_TOLERANCE: f32 = 50.0; // Kiters move at full speed once they are this far from their preferred distance
+const CHARGE_RECOVER_TIME: f32 = 0.75; // Seconds a charger stands still after lunging
+const SWARM_COHESION: f32 = 0.5; // How strongly swarmers steer towards the middle of their neighbours
+const SWARM_ALIGNMENT: f32 = 1.0; // How strongly swarmers steer in the direction their neighbours are heading
+
+#[derive(Component)]
+pub struct Chaser;
+
+#[derive(Component)]
+pub struct Kiter;
+
+#[derive(Component)]
+pub struct Charger {
+    state: ChargeState,
+    timer: Timer, // Time left in the current state, unused while approaching
+    lunge_direction: Vec3,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum ChargeState {
+    Approach,
+    WindUp,
+    Lunge,
+    Recover,
+}
+
+#[derive(Component)]
+pub struct Flanker {
+    clockwise: bool, // Half of the flankers circle the other way
+}
+
+#[derive(Component)]
+pub struct Swarmer;
+
+// Behaviours move enemies relative to their target, see enemies::targeting
+pub fn add_behaviour(enemy: &mut EntityCommands, behaviour: &Behaviour, rng: &mut impl Rng) {
+    match behaviour {
+        Behaviour::Chase => enemy.insert(Chaser),
+        Behaviour::Kite { .. } => enemy.insert(Kiter),
+        Behaviour::Charge { .. } => enemy.insert(Charger {
+            state: ChargeState::Approach,
+            timer: Timer::default(),
+            lunge_direction: Vec3::ZERO,
+        }),
+        Behaviour::Flank { .. } => enemy.insert(Flanker { clockwise: rng.gen_bool(0.5) }),
+        Behaviour::Swarm { .. } => enemy.insert(Swarmer),
+    };
+}
+
+#[allow(clippy::type_complexity)]
+pub fn chase(
+    mut enemy_query: Query<(&mut Transform, &mut characters::Character, &Target), (With<Enemy>, With<Chaser>)>,
+    enemy_types: Res<characters::EnemyTypes>,
+    time: Res<Time>,
+) {
+    for (mut enemy_transform, mut enemy, target) in enemy_query.iter_mut() {
+        let direction_vector = (target.translation - enemy_transform.translation).normalize_or_zero();
+        enemy.direction_vector = direction_vector;
+
+        enemy_transform.translation += enemy_types.0[enemy.type_index].speed * direction_vector * time.delta_seconds();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn kite(
+    mut enemy_query: Query<(&mut Transform, &mut characters::Character, &Target), (With<Enemy>, With<Kiter>)>,
+    enemy_types: Res<characters::EnemyTypes>,
+    arena: Res<Arena>,
+    time: Res<Time>,
+) {
+    for (mut enemy_transform, mut enemy, target) in enemy_query.iter_mut() {
+        let enemy_info = &enemy_types.0[enemy.type_index];
+        let Behaviour::Kite { preferred_distance } = enemy_info.behaviour else {
+            continue;
+        };
+
+        // Always faces the target, even while backing away
+        let offset = target.translation - enemy_transform.translation;
+        let direction_vector = offset.normalize_or_zero();
+        enemy.direction_vector = direction_vector;
+
+        // Positive when the enemy is too far away, negative when it is too close
+        let approach = ((offset.length() - preferred_distance) / KITE_TOLERANCE).clamp(-1.0, 1.0);
+        let new_position = enemy_transform.translation + direction_vector * approach * enemy_info.speed * time.delta_seconds();
+
+        // Kiters don't back away off the screen, they can still walk on to it after spawning
+        if approach > 0.0 || helpers::is_on_screen(&new_position, &arena) {
+            enemy_transform.translation = new_position;
+        }
+    }
+}
+
+pub fn charge(
+    mut enemy_query: Query<(&mut Transform, &mut characters::Character, &Target, &mut Charger), With<Enemy>>,
+    enemy_types: Res<characters::EnemyTypes>,
+    arena: Res<Arena>,
+    time: Res<Time>,
+) {
+    for (mut enemy_transform, mut enemy, target, mut charger) in enemy_query.iter_mut() {
+        let enemy_info = &enemy_types.0[enemy.type_index];
+        let Behaviour::Charge { range, wind_up, lunge_speed, lunge_time } = enemy_info.behaviour else {
+            continue;
+        };
+
+        let offset = target.translation - enemy_transform.translation;
+        let towards_target = offset.normalize_or_zero();
+        charger.timer.tick(time.delta());
+
+        match charger.state {
+            ChargeState::Approach => {
+                enemy.direction_vector = towards_target;
+                enemy_transform.translation += towards_target * enemy_info.speed * time.delta_seconds();
+
+                // Only winds up on screen, so the player can see the lunge coming
+                if offset.length() < range && helpers::is_on_screen(&enemy_transform.translation, &arena) {
+                    charger.state = ChargeState::WindUp;
+                    charger.timer = Timer::from_seconds(wind_up, TimerMode::Once);
+                }
+            },
+            ChargeState::WindUp => {
+                enemy.direction_vector = towards_target; // Keeps turning to face the target until it lunges
+
+                if charger.timer.finished() {
+                    charger.state = ChargeState::Lunge;
+                    charger.timer = Timer::from_seconds(lunge_time, TimerMode::Once);
+                    charger.lunge_direction = towards_target;
+                }
+            },
+            ChargeState::Lunge => {
+                enemy.direction_vector = charger.lunge_direction;
+                enemy_transform.translation += charger.lunge_direction * lunge_speed * time.delta_seconds();
+
+                if charger.timer.finished() {
+                    charger.state = ChargeState::Recover;
+                    charger.timer = Timer::from_seconds(CHARGE_RECOVER_TIME, TimerMode::Once);
+                }
+            },
+            ChargeState::Recover => {
+                if charger.timer.finished() {
+                    charger.state = ChargeState::Approach;
+                }
+            },
+        }
+    }
+}
+
+// Walks at an angle to the target, which spirals in towards it
+pub fn flank(
+    mut enemy_query: Query<(&mut Transform, &mut characters::Character, &Target, &Flanker), With<Enemy>>,
+    enemy_types: Res<characters::EnemyTypes>,
+    time: Res<Time>,
+) {
+    for (mut enemy_transform, mut enemy, target, flanker) in enemy_query.iter_mut() {
+        let enemy_info = &enemy_types.0[enemy.type_index];
+        let Behaviour::Flank { spiral_angle } = enemy_info.behaviour else {
+            continue;
+        };
+
+        let angle = match flanker.clockwise {
+            true => -spiral_angle.to_radians(),
+            false => spiral_angle.to_radians(),
+        };
+        let towards_target = (target.translation - enemy_transform.translation).normalize_or_zero();
+        let direction_vector = Quat::from_rotation_z(angle) * towards_target;
+        enemy.direction_vector = direction_vector;
+
+        enemy_transform.translation += direction_vector * enemy_info.speed * time.delta_seconds();
+    }
+}
+
+// Steers towards the middle of nearby swarmers and in the direction they are heading, as well as towards the target
+#[allow(clippy::type_complexity)]
+pub fn swarm(
+    mut enemy_query: Query<(&mut Transform, &mut characters::Character, &Target, Entity), (With<Enemy>, With<Swarmer>)>,
+    enemy_types: Res<characters::EnemyTypes>,
+    spatial_grid: Res<spatial::SpatialGrid>,
+    time: Res<Time>,
+) {
+    // Directions from the last tick, so swarmers don't depend on the order they are moved in
+    let headings: HashMap<Entity, Vec3> = enemy_query.iter()
+        .map(|(_, enemy, _, entity)| (entity, enemy.direction_vector))
+        .collect();
+
+    for (mut enemy_transform, mut enemy, target, entity) in enemy_query.iter_mut() {
+        let enemy_info = &enemy_types.0[enemy.type_index];
+        let Behaviour::Swarm { neighbour_radius } = enemy_info.behaviour else {
+            continue;
+        };
+
+        let translation = enemy_transform.translation;
+        let mut neighbour_center = Vec3::ZERO;
+        let mut neighbour_heading = Vec3::ZERO;
+        let mut neighbours = 0;
+        for entry in spatial_grid.within_radius(&translation, neighbour_radius) {
+            if entry.entity == entity {
+                continue;
+            }
+
+            // Only other swarmers are followed
+            if let Some(heading) = headings.get(&entry.entity) {
+                neighbour_center += entry.position;
+                neighbour_heading += *heading;
+                neighbours += 1;
+            }
+        }
+
+        let mut direction_vector = (target.translation - translation).normalize_or_zero();
+        if neighbours > 0 {
+            let cohesion = (neighbour_center / neighbours as f32 - translation).normalize_or_zero();
+            let alignment = neighbour_heading.normalize_or_zero();
+            direction_vector = (direction_vector + cohesion * SWARM_COHESION + alignment * SWARM_ALIGNMENT).normalize_or_zero();
+        }
+        enemy.direction_vector = direction_vector;
+
+        enemy_transform.translation += direction_vector * enemy_info.speed * time.delta_seconds();
+    }
+}
@@ -0,0 +1,155 @@
+use std::f32::consts::TAU;
+use bevy::prelude::*;
+
+use crate::{art, game};
+use game::{characters::{self, BossAttack, BossInfo}, helpers, interpolation, projectiles, rng::GameRng, Arena, GameComponent};
+use super::{spawner, Enemy, Target};
+
+// Bosses spawn alone on boss waves, see Difficulty::boss_wave
+// They walk at their target and attack with the attacks of their current phase, the phase changes as the boss loses health
+// The wave ends once the boss is dead, enemies it summoned are despawned with it
+
+const SUMMON_RADIUS: f32 = 150.0; // Distance from the boss that summoned enemies spawn at
+
+#[derive(Component)]
+pub struct Boss {
+    pub phase: usize, // Index in the boss's phases
+    attack_timer: Timer, // Time until the next attack
+    next_attack: usize, // Index in the current phase's attacks
+    lunge: Option<(Vec3, f32, Timer)>, // Direction, speed and time left of a charge attack, the boss doesn't walk while it lunges
+}
+
+impl Boss {
+    pub fn new(boss_info: &BossInfo) -> Self {
+        Boss {
+            phase: 0,
+            attack_timer: Timer::from_seconds(boss_info.phases[0].attack_rate, TimerMode::Repeating),
+            next_attack: 0,
+            lunge: None,
+        }
+    }
+}
+
+// Enemy summoned by a boss, it doesn't need to be killed to end the wave
+#[derive(Component)]
+pub struct BossAdd;
+
+// Moves bosses to the last phase they have lost enough health for, phases are never skipped backwards
+pub fn update_phases(
+    mut boss_query: Query<(&characters::Character, &mut Boss)>,
+    enemy_types: Res<characters::EnemyTypes>,
+) {
+    for (enemy, mut boss) in boss_query.iter_mut() {
+        let enemy_info = &enemy_types.0[enemy.type_index];
+        let boss_info = enemy_info.boss.as_ref().unwrap();
+
+        let health = enemy.health as f32 / enemy_info.max_health as f32;
+        let phase = boss_info.phases.iter().rposition(|phase| health <= phase.health).unwrap_or(0);
+
+        if phase > boss.phase {
+            boss.phase = phase;
+            boss.next_attack = 0;
+            boss.attack_timer = Timer::from_seconds(boss_info.phases[phase].attack_rate, TimerMode::Repeating);
+        }
+    }
+}
+
+// Bosses walk straight at their target, or in a straight line while lunging
+pub fn move_bosses(
+    mut boss_query: Query<(&mut Transform, &mut characters::Character, &Target, &mut Boss), With<Enemy>>,
+    enemy_types: Res<characters::EnemyTypes>,
+    time: Res<Time>,
+) {
+    for (mut boss_transform, mut enemy, target, mut boss) in boss_query.iter_mut() {
+        if let Some((direction_vector, lunge_speed, timer)) = boss.lunge.as_mut() {
+            enemy.direction_vector = *direction_vector;
+            boss_transform.translation += *direction_vector * *lunge_speed * time.delta_seconds();
+
+            timer.tick(time.delta());
+            if timer.finished() {
+                boss.lunge = None;
+            }
+            continue;
+        }
+
+        let direction_vector = (target.translation - boss_transform.translation).normalize_or_zero();
+        enemy.direction_vector = direction_vector;
+        boss_transform.translation += enemy_types.0[enemy.type_index].speed * direction_vector * time.delta_seconds();
+    }
+}
+
+// Uses the next attack of the boss's phase every time the attack timer finishes
+// Like shooting, bosses only attack while they are on screen
+#[allow(clippy::too_many_arguments)]
+pub fn attack(
+    mut commands: Commands,
+    mut boss_query: Query<(&Transform, &characters::Character, &Target, &mut Boss, Entity)>,
+    arena: Res<Arena>,
+    enemy_types: Res<characters::EnemyTypes>,
+    projectile_types: Res<projectiles::ProjectileTypes>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    spawn_queue: Res<spawner::SpawnQueue>,
+    mut game_rng: ResMut<GameRng>,
+    time: Res<Time>,
+) {
+    for (boss_transform, enemy, target, mut boss, entity) in boss_query.iter_mut() {
+        let boss_translation = boss_transform.translation;
+        if !helpers::is_on_screen(&boss_translation, &arena) {
+            continue;
+        }
+
+        boss.attack_timer.tick(time.delta());
+        if !boss.attack_timer.just_finished() {
+            continue;
+        }
+
+        let attacks = &enemy_types.0[enemy.type_index].boss.as_ref().unwrap().phases[boss.phase].attacks;
+        let attack = attacks[boss.next_attack % attacks.len()];
+        boss.next_attack += 1;
+
+        match attack {
+            BossAttack::Ring { projectile_types_index, projectiles } => {
+                for i in 0..projectiles {
+                    let angle = i as f32 * TAU / projectiles as f32;
+
+                    commands.spawn(
+                        (
+                            projectiles::Projectile {
+                                direction_vector: Vec3::new(angle.cos(), angle.sin(), 0.0),
+                                projectile_types_index,
+                                distance: 0.0,
+                                shooter: entity,
+                            },
+                            GameComponent,
+                            interpolation::Interpolated::new(boss_translation),
+                            SpriteBundle {
+                                transform: Transform {
+                                    translation: boss_translation,
+                                    scale: Vec3::splat(art::SPRITE_SCALE),
+                                    ..default()
+                                },
+                                texture: asset_server.load(projectile_types.0[projectile_types_index].sprite_information.sprite_path.to_string()),
+                                ..default()
+                            }
+                        )
+                    );
+                }
+            },
+            BossAttack::Summon { enemy_type, count } => {
+                for i in 0..count {
+                    let angle = i as f32 * TAU / count as f32;
+                    let position = boss_translation + Vec3::new(angle.cos(), angle.sin(), 0.0) * SUMMON_RADIUS;
+
+                    let add = spawner::new_enemy(enemy_type, &spawn_queue.modifier, &enemy_types, &mut game_rng.0);
+                    let add = spawner::spawn_enemy(&mut commands, add, position, &enemy_types, &asset_server, &mut texture_atlas_layouts, &mut game_rng.0);
+                    commands.entity(add).insert(BossAdd);
+                }
+            },
+            BossAttack::Charge { lunge_speed, lunge_time } => {
+                let direction_vector = (target.translation - boss_translation).normalize_or_zero();
+                boss.lunge = Some((direction_vector, lunge_speed, Timer::from_seconds(lunge_time, TimerMode::Once)));
+            },
+        }
+    }
+}
@@ -1,116 +1,219 @@
+use std::collections::VecDeque;
 use rand::Rng;
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
-use std::time::{Instant, Duration};
 
 use crate::{game, art};
-use game::{difficulty_settings, characters::EnemyTypes, characters, animation, NANOS_PER_MILLIS};
-use super::{EnemyPositions, Enemy};
+use game::{difficulty_settings, characters::EnemyTypes, characters, animation, interpolation, rng::GameRng, waves::{SpawnSide, WaveModifier, WaveScript}, Arena};
+use super::{behaviours, Boss, Enemy, Target};
 
-// Spawns all enemies for the wave
-pub fn spawn_enemies(
+// Enemies waiting to spawn this wave, ordered by their delay
+// The wave doesn't end until every queued enemy has spawned
+#[derive(Resource, Default, Debug)]
+pub struct SpawnQueue {
+    pub spawns: VecDeque<QueuedSpawn>,
+    pub modifier: WaveModifier, // Applied to every enemy spawned this wave
+    elapsed: f32, // Seconds since the wave started
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct QueuedSpawn {
+    pub delay: f32, // Seconds after the wave starts that the enemy spawns
+    pub enemy_type: usize,
+    pub side: SpawnSide,
+}
+
+// Queues every enemy for the wave
+// Scripted waves spawn their groups, boss waves only spawn the boss, and other waves spawn bursts of enemies picked from the spawn chances
+pub fn queue_enemies(
+    mut spawn_queue: ResMut<SpawnQueue>,
+    difficulty: Res<game::Difficulty>,
+    wave_script: Res<WaveScript>,
+    enemy_types: Res<EnemyTypes>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.0;
+    *spawn_queue = SpawnQueue::default();
+
+    if let Some(scripted_wave) = wave_script.wave(difficulty.wave) {
+        spawn_queue.modifier = scripted_wave.modifier;
+        for group in scripted_wave.groups.iter() {
+            for _ in 0..group.count {
+                spawn_queue.spawns.push_back(QueuedSpawn { delay: group.delay, enemy_type: group.enemy_type, side: group.side });
+            }
+        }
+
+        // Stable, so groups with the same delay spawn in the order they are listed
+        spawn_queue.spawns.make_contiguous().sort_by(|a, b| a.delay.total_cmp(&b.delay));
+        return;
+    }
+
+    // Bosses take turns, if the content has no bosses boss waves are normal waves
+    let bosses = enemy_types.bosses();
+    if difficulty.boss_wave() && !bosses.is_empty() {
+        let boss_wave = (difficulty.wave + 1) / difficulty_settings::BOSS_WAVE_INTERVAL - 1;
+        let enemy_type = bosses[boss_wave as usize % bosses.len()];
+
+        spawn_queue.spawns.push_back(QueuedSpawn { delay: 0.0, enemy_type, side: SpawnSide::Any });
+        return;
+    }
+   
+    for _ in 0..difficulty.enemies as usize {
+        let enemy_spawn_chance = &difficulty.enemy_spawn_chance;
+
+        // Select which enemy to spawn based on the enemy spawn chances defined in the diffuclty resource
+        for (j, spawn_chance) in enemy_spawn_chance.iter().enumerate() {
+            if (100 - *spawn_chance as i8).abs() <= rng.gen_range(1..101) {
+                spawn_queue.spawns.push_back(QueuedSpawn { delay: 0.0, enemy_type: j, side: SpawnSide::Any });
+                break;
+            }
+        }
+    }
+
+    // Spread the enemies into bursts, the first burst spawns as soon as the wave starts
+    let bursts = spawn_queue.spawns.len().div_ceil(difficulty_settings::SPAWN_BURST_SIZE);
+    for (i, queued) in spawn_queue.spawns.iter_mut().enumerate() {
+        let progress = (i / difficulty_settings::SPAWN_BURST_SIZE) as f32 / bursts as f32;
+        queued.delay = difficulty_settings::SPAWN_TIME * progress.powf(difficulty_settings::SPAWN_RATE_CURVE);
+    }
+}
+
+// Spawns queued enemies once their delay has passed
+// Enemies that are due wait while there are too many enemies alive, and spawn as soon as enough have died
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_queued(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    mut spawn_queue: ResMut<SpawnQueue>,
+    enemy_query: Query<(), With<Enemy>>,
+    arena: Res<Arena>,
     difficulty: Res<game::Difficulty>,
     enemy_types: Res<EnemyTypes>,
     asset_server: Res<AssetServer>,
-    mut enemy_positions: ResMut<EnemyPositions>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
-    
+    mut game_rng: ResMut<GameRng>,
+    time: Res<Time>,
 ) {
-    let window = window_query.get_single().unwrap();
-    let mut rng = rand::thread_rng();
+    let rng = &mut game_rng.0;
+    spawn_queue.elapsed += time.delta_seconds();
+
+    let mut alive = enemy_query.iter().count();
+    while alive < difficulty_settings::MAX_ALIVE_ENEMIES && spawn_queue.spawns.front().is_some_and(|queued| queued.delay <= spawn_queue.elapsed) {
+        let queued = spawn_queue.spawns.pop_front().unwrap();
+        let position = spawn_position(queued.side, &arena, &difficulty, rng);
+
+        let enemy = new_enemy(queued.enemy_type, &spawn_queue.modifier, &enemy_types, rng);
+        spawn_enemy(&mut commands, enemy, position, &enemy_types, &asset_server, &mut texture_atlas_layouts, rng);
+        alive += 1;
+    }
+}
 
-    let spawn_radius = window.height() * difficulty_settings::SPAWN_RADIUS_BASE_MULTIPLIER;
+// Random point around the edge of the screen, outside of the given side
+fn spawn_position(side: SpawnSide, arena: &Arena, difficulty: &game::Difficulty, rng: &mut impl Rng) -> Vec3 {
+    let spawn_radius = arena.height * difficulty_settings::SPAWN_RADIUS_BASE_MULTIPLIER;
     let spawn_diameter = (spawn_radius + difficulty.wave as f32 * difficulty_settings::SPAWN_RADIUS_SCALER) * 2.0;
 
     let padding = 20.0; // Minimum distance between an enemy spawn and the edge of the screen
-   
-    for _ in 0..difficulty.enemies as usize {
 
-        // Spawn enemy at a random point around the edge of the screen
-        // Brute force because I'm lazy
-        let mut position: Option<Vec3> = None;
-        while position == None {
-            let test_position = Vec3::new(
-                rng.gen_range((-spawn_diameter - padding)..(window.width() + spawn_diameter + padding)),
-                rng.gen_range((-spawn_diameter - padding)..(window.height() + spawn_diameter + padding)),
-                0.0,
-            );
-
-            if test_position.x > -padding && test_position.x < window.width() + padding {
-                if test_position.y > -padding && test_position.y < window.height() + padding {
-                    continue;
-                }
-            }
+    // Brute force because I'm lazy
+    loop {
+        let test_position = Vec3::new(
+            rng.gen_range((-spawn_diameter - padding)..(arena.width + spawn_diameter + padding)),
+            rng.gen_range((-spawn_diameter - padding)..(arena.height + spawn_diameter + padding)),
+            0.0,
+        );
 
-            position = Some(test_position);
+        if test_position.x > -padding && test_position.x < arena.width + padding &&
+            test_position.y > -padding && test_position.y < arena.height + padding {
+            continue;
         }
-        let position = position.unwrap();
 
-        let enemy_spawn_chance = difficulty.enemy_spawn_chance;
-
-        // Select which enemy to spawn based on the enemy spawn chances defined in the diffuclty resource
-        let mut enemy: Option<characters::Character> = None;
-        for j in 0..enemy_spawn_chance.len() {
-            if (100 - enemy_spawn_chance[j] as i8).abs() <= rng.gen_range(1..101) {
-                enemy = Some(characters::Character {
-                    last_shot: None,
-                    summon: false,
-                    last_damage: Instant::now(),
-                    direction_vector: Vec3::ZERO,
-                    health: enemy_types.0[j].max_health,
-                    type_index: j,
-                    position_index: enemy_positions.0.len(),
-                    last_animation_frame: Instant::now() + Duration::new(0, rng.gen_range(animation::ANIMATION_START_OFFSET[0] * NANOS_PER_MILLIS..animation::ANIMATION_START_OFFSET[1] * NANOS_PER_MILLIS)),
-
-                });
-                break;
-            }
+        let on_side = match side {
+            SpawnSide::Any => true,
+            SpawnSide::Top => test_position.y >= arena.height + padding,
+            SpawnSide::Bottom => test_position.y <= -padding,
+            SpawnSide::Left => test_position.x <= -padding,
+            SpawnSide::Right => test_position.x >= arena.width + padding,
+        };
+        if on_side {
+            return test_position;
         }
+    }
+}
 
-        
-
-        if let Some(enemy) = enemy {
-            enemy_positions.0.push(Some(position));
-
-            let animation_information = enemy_types.0[enemy.type_index].animation_information;
-            let texture: Handle<Image> = asset_server.load(animation_information.spritesheet_path);
-
-            let layout = TextureAtlasLayout::from_grid(
-                animation_information.sprite_size,
-                animation_information.frames,
-                1,
-                None,
-                None,
-            );
-            let layout = texture_atlas_layouts.add(layout);
-
-            commands.spawn(
-                (
-                    enemy,
-                    Enemy,
-                    animation::AnimationSprite {
-                        frames: animation_information.frames,
-                        facing_right: animation_information.sprite_faces_right,
-                    },
-                    game::GameComponent,
-                    SpriteSheetBundle {
-                        texture: texture,
-                        atlas: TextureAtlas {
-                            layout: layout,
-                            index: 0,
-                        },
-                        transform: Transform {
-                            translation: position,
-                            scale: Vec3::splat(art::SPRITE_SCALE),
-                            ..default()
-                        },
-                        visibility: Visibility::Visible,
-                        ..default()
-                    },
-                )
-            );
-        }
+// Stats of a new enemy of the type, changed by the wave modifier
+pub fn new_enemy(type_index: usize, modifier: &WaveModifier, enemy_types: &EnemyTypes, rng: &mut impl Rng) -> characters::Character {
+    let enemy_info = &enemy_types.0[type_index];
+    characters::Character {
+        shot_timer: enemy_info.firing_rate.map(|firing_rate| Timer::from_seconds(firing_rate * modifier.firing_rate, TimerMode::Repeating)),
+        summon: false,
+        invulnarability_timer: Timer::from_seconds(enemy_info.invulnarability_time, TimerMode::Once),
+        direction_vector: Vec3::ZERO,
+        health: ((enemy_info.max_health as f32 * modifier.health).round() as i32).max(1),
+        type_index,
+        animation_timer: animation::animation_timer(rng),
+    }
+}
+
+// Spawns an enemy with it's behaviour, bosses are scaled up and move with their boss state instead
+pub fn spawn_enemy(
+    commands: &mut Commands,
+    enemy: characters::Character,
+    position: Vec3,
+    enemy_types: &EnemyTypes,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    rng: &mut impl Rng,
+) -> Entity {
+    let enemy_info = &enemy_types.0[enemy.type_index];
+    let animation_information = &enemy_info.animation_information;
+    let texture: Handle<Image> = asset_server.load(animation_information.spritesheet_path.to_string());
+
+    let layout = TextureAtlasLayout::from_grid(
+        animation_information.sprite_size,
+        animation_information.frames,
+        1,
+        None,
+        None,
+    );
+    let layout = texture_atlas_layouts.add(layout);
+
+    let scale = match &enemy_info.boss {
+        Some(boss_info) => art::SPRITE_SCALE * boss_info.scale,
+        None => art::SPRITE_SCALE,
+    };
+
+    let mut enemy_commands = commands.spawn(
+        (
+            enemy,
+            Enemy,
+            Target::default(),
+            animation::AnimationSprite {
+                frames: animation_information.frames,
+                facing_right: animation_information.sprite_faces_right,
+            },
+            game::GameComponent,
+            interpolation::Interpolated::new(position),
+            SpriteSheetBundle {
+                texture,
+                atlas: TextureAtlas {
+                    layout,
+                    index: 0,
+                },
+                transform: Transform {
+                    translation: position,
+                    scale: Vec3::splat(scale),
+                    ..default()
+                },
+                visibility: Visibility::Visible,
+                ..default()
+            },
+        )
+    );
+    match &enemy_info.boss {
+        Some(boss_info) => {
+            enemy_commands.insert(Boss::new(boss_info));
+        },
+        None => behaviours::add_behaviour(&mut enemy_commands, &enemy_info.behaviour, rng),
     }
-}
\ No newline at end of file
+
+    enemy_commands.id()
+}
@@ -0,0 +1,83 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::game::{player, summons, characters, spatial};
+use super::Enemy;
+
+// Enemies pick who to go after from the player and the summons near them
+// Every candidate gets a priority, counted in pixels so it can be weighed against how far away the candidate is
+// Closer candidates, candidates that have damaged the enemy, and summons of the type the enemy prefers are picked first
+
+const TARGET_RANGE: f32 = 600.0; // Summons further away than this are ignored, the player is always a candidate
+const PLAYER_PRIORITY: f32 = 100.0; // The player counts as this much closer than they are
+const PREFERRED_PRIORITY: f32 = 400.0; // Summons of the preferred type count as this much closer than they are
+const THREAT_PRIORITY: f32 = 150.0; // Every point of threat counts as this much closer
+const CURRENT_TARGET_PRIORITY: f32 = 100.0; // Keeps enemies from switching between targets that are about as good as each other
+const THREAT_DECAY: f32 = 0.5; // Threat lost every second
+
+#[derive(Component, Default, Debug)]
+pub struct Target {
+    pub entity: Option<Entity>, // None until a target has been picked
+    pub translation: Vec3, // Where the target was this tick, enemies move and shoot towards it
+    threat: HashMap<Entity, f32>, // Damage dealt to the enemy by each character
+}
+
+impl Target {
+
+    // The character that dealt damage to the enemy is more likely to be targeted
+    pub fn add_threat(&mut self, character: Entity, damage: i32) {
+        *self.threat.entry(character).or_insert(0.0) += damage.max(0) as f32;
+    }
+}
+
+#[allow(clippy::type_complexity)]
+pub fn select_targets(
+    mut enemy_query: Query<(&Transform, &characters::Character, &mut Target), With<Enemy>>,
+    player_query: Query<(&Transform, Entity), (With<player::Player>, Without<Enemy>)>,
+    summon_query: Query<&characters::Character, (With<summons::Summon>, Without<Enemy>)>,
+    enemy_types: Res<characters::EnemyTypes>,
+    summon_types: Res<characters::SummonTypes>,
+    spatial_grid: Res<spatial::SpatialGrid>,
+    time: Res<Time>,
+) {
+    let (player_transform, player_entity) = player_query.get_single().unwrap();
+
+    for (enemy_transform, enemy, mut target) in enemy_query.iter_mut() {
+        let translation = enemy_transform.translation;
+        let preferred_target = enemy_types.0[enemy.type_index].preferred_target;
+
+        let decay = THREAT_DECAY * time.delta_seconds();
+        target.threat.retain(|_, threat| {
+            *threat -= decay;
+            *threat > 0.0
+        });
+
+        let priority = |entity: Entity, position: Vec3, bonus: f32| {
+            let threat = target.threat.get(&entity).copied().unwrap_or(0.0);
+            let current = match target.entity == Some(entity) {
+                true => CURRENT_TARGET_PRIORITY,
+                false => 0.0,
+            };
+            bonus + threat * THREAT_PRIORITY + current - position.distance(translation)
+        };
+
+        let mut best = (player_entity, player_transform.translation, priority(player_entity, player_transform.translation, PLAYER_PRIORITY));
+        for entry in spatial_grid.within_radius(&translation, TARGET_RANGE).filter(|entry| entry.kind == spatial::SpatialKind::Summon) {
+            let Ok(summon) = summon_query.get(entry.entity) else {
+                continue;
+            };
+
+            let bonus = match preferred_target.is_some() && summon_types.0[summon.type_index].summon_type == preferred_target {
+                true => PREFERRED_PRIORITY,
+                false => 0.0,
+            };
+            let summon_priority = priority(entry.entity, entry.position, bonus);
+            if summon_priority > best.2 {
+                best = (entry.entity, entry.position, summon_priority);
+            }
+        }
+
+        target.entity = Some(best.0);
+        target.translation = best.1;
+    }
+}
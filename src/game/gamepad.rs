@@ -0,0 +1,81 @@
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+use super::{input::PlayerInputSystem, Arena};
+
+// Gamepads are read alongside the keyboard and mouse, the right stick moves a virtual cursor that is used in place of the mouse
+
+pub const STICK_DEADZONE: f32 = 0.4; // The left stick moves the player once it is pushed further than this
+pub const CURSOR_DEADZONE: f32 = 0.2; // The right stick moves the cursor once it is pushed further than this
+pub const CURSOR_SPEED: f32 = 1200.0; // Speed of the virtual cursor with the right stick pushed all the way
+
+// Cursor moved with the right stick, used instead of the mouse cursor until the mouse moves again
+#[derive(Resource, Default, Debug)]
+pub struct GamepadCursor {
+    pub position: Vec3,
+    pub active: bool,
+    last_mouse_position: Option<Vec3>,
+}
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GamepadCursor>()
+            .add_systems(PreUpdate, move_gamepad_cursor.after(InputSystem).after(super::update_arena).before(PlayerInputSystem));
+    }
+}
+
+// Position of a stick on the first gamepad that has it pushed, zero if none of them do
+pub fn stick(gamepads: &Gamepads, axes: &Axis<GamepadAxis>, x_axis: GamepadAxisType, y_axis: GamepadAxisType, deadzone: f32) -> Vec2 {
+    for gamepad in gamepads.iter() {
+        let position = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, x_axis)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, y_axis)).unwrap_or(0.0),
+        );
+
+        if position.length() > deadzone {
+            return position;
+        }
+    }
+    Vec2::ZERO
+}
+
+// True if the button is held on any gamepad
+pub fn pressed(gamepads: &Gamepads, buttons: &ButtonInput<GamepadButton>, button_type: GamepadButtonType) -> bool {
+    gamepads.iter().any(|gamepad| buttons.pressed(GamepadButton::new(gamepad, button_type)))
+}
+
+// True on the frame the button was pressed on any gamepad
+pub fn just_pressed(gamepads: &Gamepads, buttons: &ButtonInput<GamepadButton>, button_type: GamepadButtonType) -> bool {
+    gamepads.iter().any(|gamepad| buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+}
+
+// Moves the virtual cursor with the right stick, it starts from wherever the mouse cursor was
+// Uses real time so the cursor can still move while the game is paused
+fn move_gamepad_cursor(
+    mut gamepad_cursor: ResMut<GamepadCursor>,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    arena: Res<Arena>,
+    time: Res<Time<Real>>,
+) {
+    // Moving the mouse switches back to the mouse cursor
+    if arena.cursor_position.is_some() && arena.cursor_position != gamepad_cursor.last_mouse_position {
+        gamepad_cursor.active = false;
+    }
+    gamepad_cursor.last_mouse_position = arena.cursor_position;
+
+    let right_stick = stick(&gamepads, &axes, GamepadAxisType::RightStickX, GamepadAxisType::RightStickY, CURSOR_DEADZONE);
+    if right_stick == Vec2::ZERO {
+        return;
+    }
+
+    if !gamepad_cursor.active {
+        gamepad_cursor.active = true;
+        gamepad_cursor.position = arena.cursor_position.unwrap_or(Vec3::new(arena.width / 2.0, arena.height / 2.0, 0.0));
+    }
+
+    let position = gamepad_cursor.position + right_stick.extend(0.0) * CURSOR_SPEED * time.delta_seconds();
+    gamepad_cursor.position = position.clamp(Vec3::ZERO, Vec3::new(arena.width, arena.height, 0.0));
+}
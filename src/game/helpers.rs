@@ -1,5 +1,8 @@
 use bevy::prelude::*;
-use crate::game::helpers;
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::{Path, PathBuf};
+use std::{fmt, fs, io};
+use crate::game::{helpers, spatial, Arena};
 
 // Returns a - b
 pub fn vector_subtract(a: &Vec3, b: &Vec3) -> Vec3 {
@@ -11,20 +14,114 @@ pub fn vector_multiply(a: &Vec3, b: &Vec3) -> Vec3 {
     Vec3::new(a.x * b.x, a.y * b.y, a.z * b.z)
 }
 
+// Directory the game keeps it's files in, inside the user data directory of the platform
+pub fn data_directory() -> Option<PathBuf> {
+    let data_directory = if cfg!(target_os = "windows") {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+
+    data_directory.map(|directory| directory.join("hexen_havoc"))
+}
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FileError::Io(error) => write!(f, "{}", error),
+            FileError::Invalid(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+impl From<io::Error> for FileError {
+    fn from(error: io::Error) -> Self {
+        FileError::Io(error)
+    }
+}
+
+// Reads a RON file, files written by another version of the game aren't read
+pub fn load_ron<T: DeserializeOwned>(path: &Path, version: u32, file_version: impl FnOnce(&T) -> u32) -> Result<T, FileError> {
+    let file: T = ron::from_str(&fs::read_to_string(path)?)
+        .map_err(|error| FileError::Invalid(error.to_string()))?;
+
+    let found_version = file_version(&file);
+    if found_version != version {
+        return Err(FileError::Invalid(format!("unsupported version {}", found_version)));
+    }
+    Ok(file)
+}
+
+// Writes to a temporary file first, so the old file isn't lost if the game closes while saving
+pub fn save_ron<T: Serialize>(file: &T, path: &Path) -> Result<(), FileError> {
+    let text = ron::ser::to_string_pretty(file, ron::ser::PrettyConfig::default())
+        .map_err(|error| FileError::Invalid(error.to_string()))?;
+
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    let temporary_path = path.with_extension("ron.tmp");
+    fs::write(&temporary_path, text)?;
+    fs::rename(&temporary_path, path)?;
+    Ok(())
+}
+
+// Loads a file if there is one, a file that can't be read is moved out of the way so it can be looked at
+// Returns None if the file doesn't exist or couldn't be read, name is what the file holds and is only used for logging
+pub fn load_or_move_bad<T>(path: &Path, name: &str, load: impl FnOnce(&Path) -> Result<T, FileError>) -> Option<T> {
+    if !path.exists() {
+        return None;
+    }
+
+    match load(path) {
+        Ok(loaded) => Some(loaded),
+        Err(error) => {
+            let backup_path = path.with_extension("ron.bad");
+            warn!("Could not read {} {}: {}, moving it to {}", name, path.display(), error, backup_path.display());
+            if let Err(error) = fs::rename(path, &backup_path) {
+                error!("Could not move {} {}: {}", name, path.display(), error);
+            }
+            None
+        },
+    }
+}
+
 // Return true if a point is on screen
-pub fn is_on_screen(position: &Vec3, window: &Window) -> bool {
-    position.x > 0.0 && position.x < window.width() &&
-    position.y > 0.0 && position.y < window.height()
+pub fn is_on_screen(position: &Vec3, arena: &Arena) -> bool {
+    position.x > 0.0 && position.x < arena.width &&
+    position.y > 0.0 && position.y < arena.height
+}
+
+// Moves a point that is off screen to the closest point that is on screen, by is_on_screen
+pub fn clamp_to_screen(position: &Vec3, arena: &Arena) -> Vec3 {
+    const EDGE_MARGIN: f32 = 1.0; // is_on_screen doesn't count points on the edge
+
+    Vec3::new(
+        position.x.clamp(EDGE_MARGIN, arena.width - EDGE_MARGIN),
+        position.y.clamp(EDGE_MARGIN, arena.height - EDGE_MARGIN),
+        position.z,
+    )
 }
 
-// Automatically moves a transform to avoid positions defined in the vec
+// Automatically moves a transform to avoid other entities of the same kind in the spatial grid
 // Only avoids when the transform and position are within 2 * (avoid_radius) of each other
-// The transform given should be one of the components whose position is contained in (positions)
+// The entity given should be the one the transform belongs to, so it doesn't avoid itself
 // Returns true if the given transform was altered to avoid the positions
 pub fn avoid_positions(
     transform: &mut Transform,
-    positions: &Vec<Option<Vec3>>,
-    positions_index: usize,
+    spatial_grid: &spatial::SpatialGrid,
+    entity: Entity,
+    kind: spatial::SpatialKind,
     avoid_radius: f32,
     time: &Res<Time>,
 ) -> bool {
@@ -32,25 +129,58 @@ pub fn avoid_positions(
     let mut avoid_direction_vector = Vec3::ZERO;
     let mut avoid_distance: f32 = 0.0;
     let mut translation_changed = false;
-    for (i, position) in positions.iter().enumerate() {
-        if let Some(position) = position {
-            if i == positions_index {
-                continue;
-            }
-    
-            let center_difference = helpers::vector_subtract(&transform.translation, &position);
-            let clip_distance = center_difference.length() - avoid_radius * 2.0; // Positive for no clip, negative for clip
-            if clip_distance < 0.0 {
-                avoid_direction_vector += center_difference;
-                avoid_distance = clip_distance.abs();
-            }
-    
-            transform.translation += avoid_direction_vector * avoid_distance * time.delta_seconds();
-    
-            if !translation_changed {
-                translation_changed = avoid_direction_vector != Vec3::ZERO  && avoid_distance != 0.0;
-            }
+    let translation = transform.translation;
+    for entry in spatial_grid.within_radius(&translation, avoid_radius * 2.0) {
+        if entry.entity == entity || entry.kind != kind {
+            continue;
+        }
+
+        let center_difference = helpers::vector_subtract(&transform.translation, &entry.position);
+        let clip_distance = center_difference.length() - avoid_radius * 2.0; // Positive for no clip, negative for clip
+        if clip_distance < 0.0 {
+            avoid_direction_vector += center_difference;
+            avoid_distance = clip_distance.abs();
+        }
+
+        transform.translation += avoid_direction_vector * avoid_distance * time.delta_seconds();
+
+        if !translation_changed {
+            translation_changed = avoid_direction_vector != Vec3::ZERO  && avoid_distance != 0.0;
         }
     }
     translation_changed
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct TestFile {
+        version: u32,
+        value: String,
+    }
+
+    fn load(path: &Path) -> Result<TestFile, FileError> {
+        load_ron(path, 1, |file: &TestFile| file.version)
+    }
+
+    #[test]
+    fn ron_files_are_saved_and_loaded() {
+        let directory = std::env::temp_dir().join(format!("hexen_havoc_helpers_{}", std::process::id()));
+        let path = directory.join("test.ron");
+        let file = TestFile { version: 1, value: "saved".to_string() };
+
+        assert!(load_or_move_bad(&path, "test file", load).is_none());
+        save_ron(&file, &path).unwrap();
+        assert_eq!(load_or_move_bad(&path, "test file", load), Some(file));
+
+        // Files from another version are moved out of the way
+        save_ron(&TestFile { version: 2, value: "newer".to_string() }, &path).unwrap();
+        assert!(load_or_move_bad(&path, "test file", load).is_none());
+        assert!(!path.exists());
+        assert!(path.with_extension("ron.bad").exists());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
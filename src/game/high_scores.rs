@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::AppState;
+use super::{characters, helpers, summons, save, score::Score, rng::GameSeed, replay::ReplayState, Difficulty, GameState};
+
+// The best runs are kept in a file in the user data directory
+// A file that can't be read is moved out of the way and a new table is started, so a corrupt file never stops the game
+
+pub const HIGH_SCORES_VERSION: u32 = 1;
+pub const MAX_HIGH_SCORES: usize = 10;
+pub const HIGH_SCORES_FILE: &str = "high_scores.ron";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct HighScore {
+    pub score: u64,
+    pub wave: u32,
+    pub seed: u64,
+    pub date: String, // UTC date the run ended, YYYY-MM-DD
+    pub summons: Vec<(String, u32)>, // Summons used in the run, by name
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct HighScoreFile {
+    version: u32,
+    high_scores: Vec<HighScore>,
+}
+
+#[derive(Resource, Default)]
+pub struct HighScoreSettings {
+    pub path: Option<PathBuf>, // Where the table is saved, the table is only kept in memory without a path
+}
+
+// Best runs, highest score first
+#[derive(Resource, Default, Debug)]
+pub struct HighScores {
+    pub high_scores: Vec<HighScore>,
+    pub last_run_rank: Option<usize>, // Place the last run got in the table, None if it didn't make it
+}
+
+impl HighScores {
+    pub fn load(path: &Path) -> Result<HighScores, helpers::FileError> {
+        let file = helpers::load_ron(path, HIGH_SCORES_VERSION, |file: &HighScoreFile| file.version)?;
+
+        let mut high_scores = file.high_scores;
+        high_scores.sort_by_key(|high_score| std::cmp::Reverse(high_score.score));
+        high_scores.truncate(MAX_HIGH_SCORES);
+
+        Ok(HighScores {
+            high_scores,
+            last_run_rank: None,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), helpers::FileError> {
+        let file = HighScoreFile {
+            version: HIGH_SCORES_VERSION,
+            high_scores: self.high_scores.clone(),
+        };
+        helpers::save_ron(&file, path)
+    }
+
+    // Adds the run to the table if it is good enough, and returns it's place
+    pub fn insert(&mut self, high_score: HighScore) -> Option<usize> {
+        let rank = self.high_scores.partition_point(|existing| existing.score >= high_score.score);
+        if rank >= MAX_HIGH_SCORES {
+            return None;
+        }
+
+        self.high_scores.insert(rank, high_score);
+        self.high_scores.truncate(MAX_HIGH_SCORES);
+        Some(rank)
+    }
+}
+
+// Information about the current run that isn't kept anywhere else
+#[derive(Resource, Default, Debug)]
+pub struct RunRecord {
+    pub summons: Vec<u32>, // Summons spawned during the run, by summon type
+    pub replay: bool, // Replays are runs that have already been played, so they aren't added to the table
+}
+
+pub struct HighScorePlugin;
+
+impl Plugin for HighScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<HighScoreSettings>()
+            .init_resource::<HighScores>()
+            .init_resource::<RunRecord>()
+            .add_systems(Startup, load_high_scores)
+            .add_systems(OnEnter(AppState::GameSetup), reset_run_record.in_set(super::GameSetupSystem))
+            .add_systems(Update, count_summons.run_if(in_state(AppState::Game)))
+            .add_systems(OnEnter(GameState::GameOver), record_high_score);
+    }
+}
+
+// Default location of the high score table, in the user data directory of the platform
+pub fn default_path() -> Option<PathBuf> {
+    helpers::data_directory().map(|directory| directory.join(HIGH_SCORES_FILE))
+}
+
+fn load_high_scores(settings: Res<HighScoreSettings>, mut high_scores: ResMut<HighScores>) {
+    if let Some(loaded) = settings.path.as_deref().and_then(|path| helpers::load_or_move_bad(path, "high scores", HighScores::load)) {
+        *high_scores = loaded;
+    }
+}
+
+fn reset_run_record(mut run_record: ResMut<RunRecord>, summon_types: Res<characters::SummonTypes>, replay_state: Res<ReplayState>) {
+    *run_record = RunRecord {
+        summons: vec![0; summon_types.0.len()],
+        replay: matches!(*replay_state, ReplayState::Playing(_)),
+    };
+}
+
+fn count_summons(
+    mut run_record: ResMut<RunRecord>,
+    summon_query: Query<&characters::Character, (Added<summons::Summon>, Without<save::Restored>)>,
+) {
+    for summon in summon_query.iter() {
+        if let Some(count) = run_record.summons.get_mut(summon.type_index) {
+            *count += 1;
+        }
+    }
+}
+
+pub fn record_high_score(
+    settings: Res<HighScoreSettings>,
+    mut high_scores: ResMut<HighScores>,
+    score: Res<Score>,
+    difficulty: Res<Difficulty>,
+    game_seed: Res<GameSeed>,
+    run_record: Res<RunRecord>,
+    summon_types: Res<characters::SummonTypes>,
+) {
+    high_scores.last_run_rank = None;
+    if run_record.replay {
+        return;
+    }
+
+    let summons = run_record.summons.iter()
+        .zip(summon_types.0.iter())
+        .filter(|(count, _)| **count > 0)
+        .map(|(count, summon_info)| (summon_info.name.clone(), *count))
+        .collect();
+
+    high_scores.last_run_rank = high_scores.insert(HighScore {
+        score: score.total(),
+        wave: difficulty.wave,
+        seed: game_seed.0,
+        date: today(),
+        summons,
+    });
+
+    if high_scores.last_run_rank.is_some() {
+        if let Some(path) = &settings.path {
+            if let Err(error) = high_scores.save(path) {
+                error!("Could not save high scores {}: {}", path.display(), error);
+            }
+        }
+    }
+}
+
+// Current UTC date as YYYY-MM-DD
+fn today() -> String {
+    let days = SystemTime::now().duration_since(UNIX_EPOCH).map(|duration| duration.as_secs() / 86400).unwrap_or(0) as i64;
+
+    // Converts days since 1970-01-01 to a calendar date, see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
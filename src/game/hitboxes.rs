@@ -1,43 +1,285 @@
 
 use bevy::prelude::*;
+use crate::art::{Hitbox, AnimationSpriteInformation};
 
-// A rectangle defined by it's min and max x and y positions
+// A hitbox placed in the world
+// Circles are capsules with no height, so there are only two shapes to test against each other
 #[derive(Debug)]
-struct Rectangle {
-    x_max: f32,
-    x_min: f32,
-    y_max: f32,
-    y_min: f32,
+enum Shape {
+    Rectangle {
+        center: Vec2,
+        half_size: Vec2,
+    },
+
+    // Vertical line from y_min to y_max, grown by the radius
+    Capsule {
+        x: f32,
+        y_min: f32,
+        y_max: f32,
+        radius: f32,
+    },
 }
 
-// Creats a rectangle from a hitbox
-fn hitbox_to_rectangle(hitbox: &Vec2, position: &Vec3) -> Rectangle {
-    let half_hitbox_width = hitbox.x / 2.0;
-    let half_hitbox_height = hitbox.y / 2.0;
+impl Hitbox {
+    pub fn offset(&self) -> Vec2 {
+        match self {
+            Hitbox::Aabb { offset, .. } | Hitbox::Circle { offset, .. } | Hitbox::Capsule { offset, .. } => *offset,
+        }
+    }
 
-    Rectangle {
-        x_max: position.x + half_hitbox_width,
-        x_min: position.x - half_hitbox_width,
-        y_max: position.y + half_hitbox_height,
-        y_min: position.y - half_hitbox_height,
+    pub fn with_offset(mut self, new_offset: Vec2) -> Hitbox {
+        match &mut self {
+            Hitbox::Aabb { offset, .. } | Hitbox::Circle { offset, .. } | Hitbox::Capsule { offset, .. } => *offset = new_offset,
+        }
+        self
+    }
+
+    // Half the width and height of the smallest rectangle around the hitbox
+    pub fn half_size(&self) -> Vec2 {
+        match self {
+            Hitbox::Aabb { size, .. } => *size / 2.0,
+            Hitbox::Circle { radius, .. } => Vec2::splat(*radius),
+            Hitbox::Capsule { radius, height, .. } => Vec2::new(*radius, (height / 2.0).max(*radius)),
+        }
+    }
+
+    // How far the hitbox reaches from the position it is placed at, on each axis
+    pub fn reach(&self) -> Vec2 {
+        self.offset().abs() + self.half_size()
+    }
+
+    // Scales the size and offset of the hitbox
+    pub fn scaled(self, scale: f32) -> Hitbox {
+        match self {
+            Hitbox::Aabb { size, offset } => Hitbox::Aabb { size: size * scale, offset: offset * scale },
+            Hitbox::Circle { radius, offset } => Hitbox::Circle { radius: radius * scale, offset: offset * scale },
+            Hitbox::Capsule { radius, height, offset } => Hitbox::Capsule { radius: radius * scale, height: height * scale, offset: offset * scale },
+        }
+    }
+
+    fn shape(&self, position: &Vec3) -> Shape {
+        let center = position.truncate() + self.offset();
+
+        match *self {
+            Hitbox::Aabb { size, .. } => Shape::Rectangle {
+                center,
+                half_size: size / 2.0,
+            },
+            Hitbox::Circle { radius, .. } => Shape::Capsule {
+                x: center.x,
+                y_min: center.y,
+                y_max: center.y,
+                radius,
+            },
+            Hitbox::Capsule { radius, height, .. } => {
+                let half_line = (height / 2.0 - radius).max(0.0); // Capsules shorter than they are wide are circles
+                Shape::Capsule {
+                    x: center.x,
+                    y_min: center.y - half_line,
+                    y_max: center.y + half_line,
+                    radius,
+                }
+            },
+        }
+    }
+}
+
+// Hitbox of an animated sprite on the frame it is showing
+// Sprites are flipped by rotating them around the y axis or with a negative x scale, flipped sprites have their offsets mirrored
+pub fn animation_hitbox(animation_information: &AnimationSpriteInformation, transform: &Transform, frame: usize) -> Hitbox {
+    let mut offset = animation_information.hitbox.offset() + animation_information.frame_offsets.get(frame).copied().unwrap_or(Vec2::ZERO);
+    if (transform.rotation * (transform.scale * Vec3::X)).x < 0.0 {
+        offset.x = -offset.x;
     }
+    animation_information.hitbox.with_offset(offset)
+}
+
+// Distance between two ranges, 0 if they overlap
+fn range_gap(a_min: f32, a_max: f32, b_min: f32, b_max: f32) -> f32 {
+    (b_min - a_max).max(a_min - b_max).max(0.0)
 }
 
 // Returns true if hitboxes are colliding
-pub fn are_hitboxes_colliding(a: &Vec2, a_pos: &Vec3, b: &Vec2, b_pos: &Vec3) -> bool {
+// Touching edges don't count as a collision, but shapes centred on the same point always collide even if they have no size
+pub fn are_hitboxes_colliding(a: &Hitbox, a_pos: &Vec3, b: &Hitbox, b_pos: &Vec3) -> bool {
+    match (a.shape(a_pos), b.shape(b_pos)) {
+        (Shape::Rectangle { center: a_center, half_size: a_half }, Shape::Rectangle { center: b_center, half_size: b_half }) => {
+            let difference = (a_center - b_center).abs();
+            let reach = a_half + b_half;
+
+            let x_collision = difference.x < reach.x || difference.x == 0.0;
+            let y_collision = difference.y < reach.y || difference.y == 0.0;
+            x_collision && y_collision
+        },
+        (Shape::Rectangle { center, half_size }, Shape::Capsule { x, y_min, y_max, radius }) |
+        (Shape::Capsule { x, y_min, y_max, radius }, Shape::Rectangle { center, half_size }) => {
+            let gap = Vec2::new(
+                ((x - center.x).abs() - half_size.x).max(0.0),
+                range_gap(y_min, y_max, center.y - half_size.y, center.y + half_size.y),
+            );
+            gap.length() < radius || gap == Vec2::ZERO
+        },
+        (Shape::Capsule { x: a_x, y_min: a_min, y_max: a_max, radius: a_radius }, Shape::Capsule { x: b_x, y_min: b_min, y_max: b_max, radius: b_radius }) => {
+
+            // Both lines are vertical, so the closest points are found one axis at a time
+            let gap = Vec2::new(a_x - b_x, range_gap(a_min, a_max, b_min, b_max));
+            gap.length() < a_radius + b_radius || gap == Vec2::ZERO
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::borrow::Cow;
+    use std::f32::consts::PI;
+
+    fn aabb(width: f32, height: f32) -> Hitbox {
+        Hitbox::Aabb { size: Vec2::new(width, height), offset: Vec2::ZERO }
+    }
+
+    fn circle(radius: f32) -> Hitbox {
+        Hitbox::Circle { radius, offset: Vec2::ZERO }
+    }
+
+    fn capsule(radius: f32, height: f32) -> Hitbox {
+        Hitbox::Capsule { radius, height, offset: Vec2::ZERO }
+    }
+
+    // Checks both orders, collisions shouldn't depend on which hitbox is first
+    fn colliding(a: Hitbox, a_pos: Vec2, b: Hitbox, b_pos: Vec2) -> bool {
+        let (a_pos, b_pos) = (a_pos.extend(0.0), b_pos.extend(0.0));
+        let result = are_hitboxes_colliding(&a, &a_pos, &b, &b_pos);
+        assert_eq!(result, are_hitboxes_colliding(&b, &b_pos, &a, &a_pos));
+        result
+    }
+
+    #[test]
+    fn aabb_aabb() {
+        assert!(colliding(aabb(10.0, 10.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(9.0, 9.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(11.0, 0.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(0.0, -11.0)));
+
+        // Overlapping on one axis isn't enough
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(5.0, 20.0)));
+    }
+
+    #[test]
+    fn aabb_circle() {
+        assert!(colliding(aabb(10.0, 10.0), Vec2::ZERO, circle(5.0), Vec2::new(9.0, 0.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, circle(5.0), Vec2::new(11.0, 0.0)));
+
+        // Near the corner the circle is further from the box than on the axes
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, circle(5.0), Vec2::new(9.0, 9.0)));
+        assert!(colliding(aabb(10.0, 10.0), Vec2::ZERO, circle(5.0), Vec2::new(8.0, 8.0)));
+    }
+
+    #[test]
+    fn aabb_capsule() {
+        assert!(colliding(aabb(10.0, 10.0), Vec2::ZERO, capsule(2.0, 30.0), Vec2::new(0.0, 19.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, capsule(2.0, 30.0), Vec2::new(0.0, 21.0)));
+        assert!(colliding(aabb(10.0, 10.0), Vec2::ZERO, capsule(2.0, 30.0), Vec2::new(6.0, 12.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, capsule(2.0, 30.0), Vec2::new(8.0, 12.0)));
+    }
+
+    #[test]
+    fn circle_circle() {
+        assert!(colliding(circle(5.0), Vec2::ZERO, circle(5.0), Vec2::new(6.0, 6.0)));
+        assert!(!colliding(circle(5.0), Vec2::ZERO, circle(5.0), Vec2::new(8.0, 8.0)));
+    }
+
+    #[test]
+    fn circle_capsule() {
+        // Alongside the straight part of the capsule
+        assert!(colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(9.0, 12.0)));
+        assert!(!colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(11.0, 12.0)));
+
+        // Past the end of the capsule
+        assert!(colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(0.0, 24.0)));
+        assert!(!colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(0.0, 26.0)));
+
+        // Capsules shorter than they are wide are circles
+        assert_eq!(
+            colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 4.0), Vec2::new(7.0, 7.0)),
+            colliding(circle(5.0), Vec2::ZERO, circle(5.0), Vec2::new(7.0, 7.0)),
+        );
+    }
 
-    // Test for rectangle collision before checking circles
-    // Because rectangle is a faster approximation
-    let rect_a = hitbox_to_rectangle(&a, &a_pos);
-    let rect_b = hitbox_to_rectangle(&b, &b_pos);
+    #[test]
+    fn capsule_capsule() {
+        assert!(colliding(capsule(5.0, 40.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(9.0, 30.0)));
+        assert!(!colliding(capsule(5.0, 40.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(11.0, 30.0)));
+        assert!(colliding(capsule(5.0, 40.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(0.0, 39.0)));
+        assert!(!colliding(capsule(5.0, 40.0), Vec2::ZERO, capsule(5.0, 40.0), Vec2::new(0.0, 41.0)));
+    }
+
+    #[test]
+    fn touching_edges_dont_collide() {
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(10.0, 0.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, circle(5.0), Vec2::new(10.0, 0.0)));
+        assert!(!colliding(aabb(10.0, 10.0), Vec2::ZERO, capsule(5.0, 30.0), Vec2::new(0.0, 20.0)));
+        assert!(!colliding(circle(5.0), Vec2::ZERO, circle(5.0), Vec2::new(0.0, 10.0)));
+        assert!(!colliding(circle(5.0), Vec2::ZERO, capsule(5.0, 30.0), Vec2::new(10.0, 5.0)));
+        assert!(!colliding(capsule(5.0, 30.0), Vec2::ZERO, capsule(5.0, 30.0), Vec2::new(0.0, 30.0)));
+    }
+
+    #[test]
+    fn same_centre_always_collides() {
+        let position = Vec2::new(3.0, -7.0);
+        let hitboxes = [aabb(10.0, 10.0), circle(5.0), capsule(5.0, 30.0), aabb(0.0, 0.0), circle(0.0), capsule(0.0, 0.0)];
 
-    let x_collision = a_pos.x < b_pos.x && rect_a.x_max > rect_b.x_min ||
-        a_pos.x > b_pos.x && rect_a.x_min < rect_b.x_max ||
-        a_pos.x == b_pos.x;
+        for a in hitboxes {
+            for b in hitboxes {
+                assert!(colliding(a, position, b, position), "{:?} and {:?}", a, b);
+            }
+        }
+
+        // Lined up on one axis only
+        assert!(colliding(aabb(0.0, 0.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(0.0, 4.0)));
+        assert!(!colliding(aabb(0.0, 0.0), Vec2::ZERO, aabb(10.0, 10.0), Vec2::new(0.0, 6.0)));
+    }
+
+    #[test]
+    fn zero_size_boxes() {
+        assert!(colliding(aabb(0.0, 0.0), Vec2::new(4.0, 4.0), aabb(10.0, 10.0), Vec2::ZERO));
+        assert!(!colliding(aabb(0.0, 0.0), Vec2::new(5.0, 0.0), aabb(10.0, 10.0), Vec2::ZERO));
+        assert!(!colliding(aabb(0.0, 0.0), Vec2::new(1.0, 0.0), aabb(0.0, 0.0), Vec2::ZERO));
+        assert!(colliding(aabb(0.0, 0.0), Vec2::new(4.0, 0.0), circle(5.0), Vec2::ZERO));
+    }
 
-    let y_collision = a_pos.y < b_pos.y && rect_a.y_max > rect_b.y_min ||
-        a_pos.y > b_pos.y && rect_a.y_min < rect_b.y_max ||
-        a_pos.y == b_pos.y;
+    #[test]
+    fn offsets_move_the_hitbox() {
+        let offset_box = aabb(10.0, 10.0).with_offset(Vec2::new(20.0, 0.0));
+        assert_eq!(offset_box.offset(), Vec2::new(20.0, 0.0));
+        assert_eq!(offset_box.reach(), Vec2::new(25.0, 5.0));
 
-    x_collision && y_collision
-}
\ No newline at end of file
+        assert!(colliding(offset_box, Vec2::ZERO, circle(5.0), Vec2::new(20.0, 0.0)));
+        assert!(!colliding(offset_box, Vec2::ZERO, circle(5.0), Vec2::ZERO));
+        assert!(colliding(capsule(5.0, 20.0).with_offset(Vec2::new(0.0, -30.0)), Vec2::ZERO, circle(5.0), Vec2::new(0.0, -30.0)));
+    }
+
+    #[test]
+    fn flipped_frames_mirror_offsets() {
+        let animation_information = AnimationSpriteInformation {
+            spritesheet_path: Cow::Borrowed(""),
+            sprite_faces_right: true,
+            sprite_size: Vec2::splat(100.0),
+            hitbox: circle(5.0).with_offset(Vec2::new(2.0, 1.0)),
+            frames: 2,
+            frame_offsets: vec![Vec2::ZERO, Vec2::new(10.0, 3.0)],
+        };
+        let offset = |transform: Transform, frame: usize| animation_hitbox(&animation_information, &transform, frame).offset();
+
+        assert_eq!(offset(Transform::IDENTITY, 0), Vec2::new(2.0, 1.0));
+        assert_eq!(offset(Transform::IDENTITY, 1), Vec2::new(12.0, 4.0));
+        assert_eq!(offset(Transform::IDENTITY, 5), Vec2::new(2.0, 1.0)); // Frames without an offset
+
+        let rotated = Transform::from_rotation(Quat::from_rotation_y(PI));
+        let scaled = Transform::from_scale(Vec3::new(-1.0, 1.0, 1.0));
+        assert_eq!(offset(rotated, 1), Vec2::new(-12.0, 4.0));
+        assert_eq!(offset(scaled, 1), Vec2::new(-12.0, 4.0));
+
+        // Flipping twice faces the sprite the original way
+        assert_eq!(offset(rotated.with_scale(Vec3::new(-1.0, 1.0, 1.0)), 1), Vec2::new(12.0, 4.0));
+    }
+}
@@ -0,0 +1,121 @@
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+use bevy::ui::UiSystem;
+use super::{gamepad, rewards, summons, Arena};
+use super::keybinds::{Action, Keybinds, RebindState, BUY_SUMMON_SLOTS};
+
+// Gameplay reads the player's input from this resource instead of the input devices
+// So a run can be driven by a replay, or by a test
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerInput {
+    pub up: bool,
+    pub down: bool,
+    pub left: bool,
+    pub right: bool,
+
+    pub move_ranged_summons: bool,
+    pub move_melee_summons: bool,
+    pub attack: bool, // Held to attack towards the cursor
+    pub dash: bool, // Held to dash, the player only dashes once each time it is pressed
+
+    pub pause: bool, // True on the frame pause was pressed
+    pub place_sentry: bool, // True on the frame place sentry was pressed
+    pub reward_choice: Option<usize>, // Reward card picked this frame
+    pub buy_summon: Option<usize>, // Summon bar slot picked this frame
+    pub buy_summon_clicked: bool, // True if the slot was clicked instead of picked with it's hotkey
+
+    pub cursor_position: Option<Vec3>, // Cursor position in world coordinates
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PlayerInputSystem;
+
+pub struct PlayerInputPlugin;
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PlayerInput>()
+            .add_systems(PreUpdate, read_player_input.in_set(PlayerInputSystem).after(InputSystem).after(UiSystem::Focus).after(super::update_arena));
+    }
+}
+
+// Reads the player input from the keyboard and mouse, using the actions they are bound to
+#[allow(clippy::too_many_arguments)]
+fn read_player_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    keybinds: Res<Keybinds>,
+    rebind_state: Res<State<RebindState>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    gamepad_cursor: Res<gamepad::GamepadCursor>,
+    arena: Res<Arena>,
+    reward_card_query: Query<(&Interaction, &rewards::RewardCard), Changed<Interaction>>,
+    summon_slot_query: Query<(&Interaction, &summons::SummonSlot), Changed<Interaction>>,
+    button_query: Query<&Interaction, With<Button>>,
+    mut player_input: ResMut<PlayerInput>,
+) {
+    // The virtual cursor is used once the right stick moves it, until the mouse moves again
+    let cursor_position = match gamepad_cursor.active {
+        true => Some(gamepad_cursor.position),
+        false => arena.cursor_position,
+    };
+
+    // Keys pressed in the controls menu are being rebound
+    if *rebind_state.get() == RebindState::Open {
+        *player_input = PlayerInput {
+            cursor_position,
+            ..default()
+        };
+        return;
+    }
+
+    // Mouse buttons used on the ui, like clicking the summon bar, don't also trigger the actions bound to them
+    let no_buttons = ButtonInput::<MouseButton>::default();
+    let buttons = match button_query.iter().any(|interaction| *interaction != Interaction::None) {
+        true => &no_buttons,
+        false => &*buttons,
+    };
+
+    let pressed = |action| {
+        keybinds.pressed(action, &keyboard_input, buttons)
+            || Keybinds::gamepad_button(action).is_some_and(|button_type| gamepad::pressed(&gamepads, &gamepad_buttons, button_type))
+    };
+    let just_pressed = |action| {
+        keybinds.just_pressed(action, &keyboard_input, buttons)
+            || Keybinds::gamepad_button(action).is_some_and(|button_type| gamepad::just_pressed(&gamepads, &gamepad_buttons, button_type))
+    };
+    let left_stick = gamepad::stick(&gamepads, &gamepad_axes, GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY, gamepad::STICK_DEADZONE);
+
+    let clicked_reward = reward_card_query.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, reward_card)| reward_card.0);
+    let pressed_reward = (0..rewards::REWARD_CHOICES).find(|card| just_pressed(Action::ChooseReward(*card)));
+
+    let clicked_summon = summon_slot_query.iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, summon_slot)| summon_slot.0);
+    let pressed_summon = (0..BUY_SUMMON_SLOTS).find(|slot| just_pressed(Action::BuySummon(*slot)));
+
+    *player_input = PlayerInput {
+        up: pressed(Action::MoveUp) || left_stick.y > gamepad::STICK_DEADZONE,
+        down: pressed(Action::MoveDown) || left_stick.y < -gamepad::STICK_DEADZONE,
+        left: pressed(Action::MoveLeft) || left_stick.x < -gamepad::STICK_DEADZONE,
+        right: pressed(Action::MoveRight) || left_stick.x > gamepad::STICK_DEADZONE,
+
+        move_ranged_summons: pressed(Action::MoveRanged),
+        move_melee_summons: pressed(Action::MoveMelee),
+        attack: pressed(Action::Attack),
+        dash: pressed(Action::Dash),
+
+        pause: just_pressed(Action::Pause),
+        place_sentry: just_pressed(Action::PlaceSentry),
+        reward_choice: clicked_reward.or(pressed_reward),
+        buy_summon: clicked_summon.or(pressed_summon),
+        buy_summon_clicked: clicked_summon.is_some(),
+
+        cursor_position,
+    };
+}
@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+
+// Gameplay moves entities in FixedUpdate, so without interpolation they would only move on frames where a tick ran
+// Entities with this component are drawn between their last two simulated positions
+#[derive(Component, Debug)]
+pub struct Interpolated {
+    previous: Vec3, // Translation after the second to last tick
+    current: Vec3, // Translation after the last tick
+}
+
+impl Interpolated {
+    pub fn new(translation: Vec3) -> Self {
+        Interpolated {
+            previous: translation,
+            current: translation,
+        }
+    }
+
+    // Simulated translation, the transform may be between ticks
+    pub fn translation(&self) -> Vec3 {
+        self.current
+    }
+}
+
+pub struct InterpolationPlugin;
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedFirst, restore_translations)
+            .add_systems(FixedLast, store_translations)
+            .add_systems(Update, interpolate_translations);
+    }
+}
+
+// Puts entities back at their simulated translation before the next tick
+fn restore_translations(mut interpolated_query: Query<(&mut Transform, &mut Interpolated)>) {
+    for (mut transform, mut interpolated) in interpolated_query.iter_mut() {
+        transform.translation = interpolated.current;
+        interpolated.previous = interpolated.current;
+    }
+}
+
+fn store_translations(mut interpolated_query: Query<(&Transform, &mut Interpolated)>) {
+    for (transform, mut interpolated) in interpolated_query.iter_mut() {
+        interpolated.current = transform.translation;
+    }
+}
+
+// Moves entities between their last two simulated translations, by how far the fixed clock is into the next tick
+pub fn interpolate_translations(mut interpolated_query: Query<(&mut Transform, &Interpolated)>, fixed_time: Res<Time<Fixed>>) {
+    let overstep = fixed_time.overstep_fraction();
+    for (mut transform, interpolated) in interpolated_query.iter_mut() {
+        transform.translation = interpolated.previous.lerp(interpolated.current, overstep);
+    }
+}
@@ -1,6 +1,294 @@
-use bevy::prelude::KeyCode;
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::fmt;
 
-pub const PLAYER_UP: KeyCode = KeyCode::KeyW;
-pub const PLAYER_LEFT: KeyCode = KeyCode::KeyA;
-pub const PLAYER_DOWN: KeyCode = KeyCode::KeyS;
-pub const PLAYER_RIGHT: KeyCode = KeyCode::KeyD;
\ No newline at end of file
+use super::{helpers, input::PlayerInputSystem, rewards};
+
+// Gameplay reads actions instead of keys, the key or mouse button bound to every action is loaded from a file
+
+pub const KEYBINDS_VERSION: u32 = 1;
+pub const KEYBINDS_FILE: &str = "keybinds.ron";
+
+pub const BUY_SUMMON_SLOTS: usize = 9; // Summon bar slots that have a hotkey
+
+// Gamepad buttons for actions, the left stick moves the player and the d-pad moves between ui buttons
+pub const GAMEPAD_BINDINGS: &[(Action, GamepadButtonType)] = &[
+    (Action::MoveRanged, GamepadButtonType::RightTrigger2),
+    (Action::MoveMelee, GamepadButtonType::LeftTrigger2),
+    (Action::Pause, GamepadButtonType::Start),
+    (Action::PlaceSentry, GamepadButtonType::West),
+    (Action::Attack, GamepadButtonType::RightTrigger),
+    (Action::Dash, GamepadButtonType::LeftTrigger),
+];
+
+// Presses the ui button selected with the d-pad
+pub const GAMEPAD_CONFIRM: GamepadButtonType = GamepadButtonType::South;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    MoveRanged, // Moves ranged summons towards the cursor
+    MoveMelee, // Moves melee summons towards the cursor
+    Pause,
+    PlaceSentry,
+    Attack, // Fires a hex from the player's staff towards the cursor
+    Dash, // Blinks the player in the direction they are moving
+    BuySummon(usize), // Buys the summon in this slot of the summon bar
+    ChooseReward(usize), // Picks this reward card, reward cards can also be clicked
+}
+
+impl Action {
+
+    // Every action, in the order they are shown in the controls menu
+    pub fn all() -> Vec<Action> {
+        let mut actions = vec![
+            Action::MoveUp,
+            Action::MoveDown,
+            Action::MoveLeft,
+            Action::MoveRight,
+            Action::MoveRanged,
+            Action::MoveMelee,
+            Action::Pause,
+            Action::PlaceSentry,
+            Action::Attack,
+            Action::Dash,
+        ];
+        actions.extend((0..BUY_SUMMON_SLOTS).map(Action::BuySummon));
+        actions.extend((0..rewards::REWARD_CHOICES).map(Action::ChooseReward));
+        actions
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Action::MoveUp => "Move Up".to_string(),
+            Action::MoveDown => "Move Down".to_string(),
+            Action::MoveLeft => "Move Left".to_string(),
+            Action::MoveRight => "Move Right".to_string(),
+            Action::MoveRanged => "Move Ranged Summons".to_string(),
+            Action::MoveMelee => "Move Melee Summons".to_string(),
+            Action::Pause => "Pause".to_string(),
+            Action::PlaceSentry => "Place Sentry".to_string(),
+            Action::Attack => "Attack".to_string(),
+            Action::Dash => "Dash".to_string(),
+            Action::BuySummon(slot) => format!("Buy Summon {}", slot + 1),
+            Action::ChooseReward(card) => format!("Choose Reward {}", card + 1),
+        }
+    }
+
+    // Rewards are only chosen between waves when summons can't be bought, so they can share keys
+    fn can_share_binding(&self, other: &Action) -> bool {
+        matches!((self, other), (Action::BuySummon(_), Action::ChooseReward(_)) | (Action::ChooseReward(_), Action::BuySummon(_)))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    pub fn pressed(&self, keyboard_input: &ButtonInput<KeyCode>, buttons: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Binding::Key(key) => keyboard_input.pressed(*key),
+            Binding::Mouse(button) => buttons.pressed(*button),
+        }
+    }
+
+    pub fn just_pressed(&self, keyboard_input: &ButtonInput<KeyCode>, buttons: &ButtonInput<MouseButton>) -> bool {
+        match self {
+            Binding::Key(key) => keyboard_input.just_pressed(*key),
+            Binding::Mouse(button) => buttons.just_pressed(*button),
+        }
+    }
+}
+
+// Short name shown in the ui, eg. KeyW is shown as W
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Binding::Key(key) => {
+                let name = format!("{:?}", key);
+                let name = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")).unwrap_or(&name);
+                write!(f, "{}", name)
+            },
+            Binding::Mouse(MouseButton::Other(button)) => write!(f, "Mouse {}", button),
+            Binding::Mouse(button) => write!(f, "{:?} Mouse", button),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct KeybindFile {
+    version: u32,
+    bindings: Vec<(Action, Binding)>,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Keybinds(pub HashMap<Action, Binding>);
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        let mut bindings = HashMap::new();
+        bindings.insert(Action::MoveUp, Binding::Key(KeyCode::KeyW));
+        bindings.insert(Action::MoveDown, Binding::Key(KeyCode::KeyS));
+        bindings.insert(Action::MoveLeft, Binding::Key(KeyCode::KeyA));
+        bindings.insert(Action::MoveRight, Binding::Key(KeyCode::KeyD));
+        bindings.insert(Action::MoveRanged, Binding::Mouse(MouseButton::Left));
+        bindings.insert(Action::MoveMelee, Binding::Mouse(MouseButton::Right));
+        bindings.insert(Action::Pause, Binding::Key(KeyCode::Escape));
+        bindings.insert(Action::PlaceSentry, Binding::Key(KeyCode::KeyQ));
+        bindings.insert(Action::Attack, Binding::Key(KeyCode::Space));
+        bindings.insert(Action::Dash, Binding::Key(KeyCode::ShiftLeft));
+
+        let digits = [
+            KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3,
+            KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6,
+            KeyCode::Digit7, KeyCode::Digit8, KeyCode::Digit9,
+        ];
+        for (slot, key) in digits.iter().enumerate().take(BUY_SUMMON_SLOTS) {
+            bindings.insert(Action::BuySummon(slot), Binding::Key(*key));
+        }
+        for (card, key) in digits.iter().enumerate().take(rewards::REWARD_CHOICES) {
+            bindings.insert(Action::ChooseReward(card), Binding::Key(*key));
+        }
+
+        Keybinds(bindings)
+    }
+}
+
+impl Keybinds {
+    // Gamepad button for an action, gamepad buttons can't be rebound
+    pub fn gamepad_button(action: Action) -> Option<GamepadButtonType> {
+        GAMEPAD_BINDINGS.iter().find(|(bound_action, _)| *bound_action == action).map(|(_, button_type)| *button_type)
+    }
+
+    pub fn pressed(&self, action: Action, keyboard_input: &ButtonInput<KeyCode>, buttons: &ButtonInput<MouseButton>) -> bool {
+        self.0.get(&action).is_some_and(|binding| binding.pressed(keyboard_input, buttons))
+    }
+
+    pub fn just_pressed(&self, action: Action, keyboard_input: &ButtonInput<KeyCode>, buttons: &ButtonInput<MouseButton>) -> bool {
+        self.0.get(&action).is_some_and(|binding| binding.just_pressed(keyboard_input, buttons))
+    }
+
+    // Another action that already uses the binding, and can't share it with the given action
+    pub fn conflict(&self, action: Action, binding: Binding) -> Option<Action> {
+        Action::all().into_iter()
+            .find(|other| *other != action && !action.can_share_binding(other) && self.0.get(other) == Some(&binding))
+    }
+
+    // Actions missing from the file keep their default binding, so new actions can be added without breaking old files
+    pub fn load(path: &Path) -> Result<Keybinds, helpers::FileError> {
+        let file = helpers::load_ron(path, KEYBINDS_VERSION, |file: &KeybindFile| file.version)?;
+
+        let mut keybinds = Keybinds::default();
+        for (action, binding) in file.bindings {
+            keybinds.0.insert(action, binding);
+        }
+        Ok(keybinds)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), helpers::FileError> {
+        let file = KeybindFile {
+            version: KEYBINDS_VERSION,
+            bindings: Action::all().into_iter()
+                .filter_map(|action| self.0.get(&action).map(|binding| (action, *binding)))
+                .collect(),
+        };
+        helpers::save_ron(&file, path)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct KeybindSettings {
+    pub path: Option<PathBuf>, // Where the bindings are saved, the bindings are only kept in memory without a path
+}
+
+// Open while the controls menu is shown, gameplay ignores the player's input so keys can be pressed to rebind them
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum RebindState {
+    #[default]
+    Closed,
+    Open,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Rebinding {
+    pub action: Option<Action>, // The next key or mouse button pressed is bound to this action
+    pub conflict: Option<(Binding, Action)>, // The last binding that couldn't be used, and the action already using it
+}
+
+pub struct KeybindPlugin;
+
+impl Plugin for KeybindPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<RebindState>()
+            .init_resource::<KeybindSettings>()
+            .init_resource::<Keybinds>()
+            .init_resource::<Rebinding>()
+            .add_systems(Startup, load_keybinds)
+            .add_systems(OnExit(RebindState::Open), stop_rebinding)
+            .add_systems(PreUpdate, rebind_action.run_if(in_state(RebindState::Open)).after(InputSystem).before(PlayerInputSystem));
+    }
+}
+
+// Default location of the bindings, in the user data directory of the platform
+pub fn default_path() -> Option<PathBuf> {
+    helpers::data_directory().map(|directory| directory.join(KEYBINDS_FILE))
+}
+
+// Saves the bindings, they are only kept in memory if there is no path
+pub fn save_keybinds(settings: &KeybindSettings, keybinds: &Keybinds) {
+    if let Some(path) = &settings.path {
+        if let Err(error) = keybinds.save(path) {
+            error!("Could not save keybinds {}: {}", path.display(), error);
+        }
+    }
+}
+
+fn load_keybinds(settings: Res<KeybindSettings>, mut keybinds: ResMut<Keybinds>) {
+    // The default bindings are used if the file can't be read
+    if let Some(loaded) = settings.path.as_deref().and_then(|path| helpers::load_or_move_bad(path, "keybinds", Keybinds::load)) {
+        *keybinds = loaded;
+    }
+}
+
+fn stop_rebinding(mut rebinding: ResMut<Rebinding>) {
+    *rebinding = Rebinding::default();
+}
+
+// Binds the next key or mouse button pressed to the action being rebound, unless another action already uses it
+fn rebind_action(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    settings: Res<KeybindSettings>,
+    mut keybinds: ResMut<Keybinds>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let Some(action) = rebinding.action else {
+        return;
+    };
+
+    let binding = keyboard_input.get_just_pressed().next().map(|key| Binding::Key(*key))
+        .or_else(|| buttons.get_just_pressed().next().map(|button| Binding::Mouse(*button)));
+    let Some(binding) = binding else {
+        return;
+    };
+
+    rebinding.action = None;
+    if let Some(other) = keybinds.conflict(action, binding) {
+        rebinding.conflict = Some((binding, other));
+        return;
+    }
+
+    rebinding.conflict = None;
+    keybinds.0.insert(action, binding);
+    save_keybinds(&settings, &keybinds);
+}
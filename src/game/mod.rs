@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
 use crate::AppState;
 
 pub mod player;
@@ -12,15 +13,24 @@ pub mod summons;
 pub mod damage;
 pub mod collectibles;
 pub mod animation;
-
-pub const NANOS_PER_MILLIS: u32 = 1000000;
+pub mod content;
+pub mod rng;
+pub mod input;
+pub mod replay;
+pub mod interpolation;
+pub mod spatial;
+pub mod rewards;
+pub mod score;
+pub mod high_scores;
+pub mod save;
+pub mod gamepad;
+pub mod waves;
 
 pub const WAVE_COUNTDOWN_SECONDS: u8 = 3; // Seconds inbetween waves
+pub const TICK_RATE: f64 = 60.0; // Default number of times gameplay is simulated every second
 
 pub mod difficulty_settings {
-    use super::characters;
-    
-    pub const STARTING_COLLECTIBLES: [i32; characters::COLLECTIBLE_TYPES] = [12, 0, 0, 0]; // Starting collectibles for the player
+    pub const STARTING_COLLECTIBLES: &[i32] = &[12, 0, 0, 0]; // Starting collectibles for the player, by collectible type
 
     pub const STARTING_ENEMIES: f32 = 9.0; // Number of enemies on wave 0
     pub const ENEMIES_MULTIPLIER: f32 = 1.2; // Multiply the number of enemies from last wave by this number to get the new number of enemies for a wave
@@ -29,6 +39,14 @@ pub mod difficulty_settings {
     // Defines how large the enemy spawn radius is
     pub const SPAWN_RADIUS_BASE_MULTIPLIER: f32 = 0.25; // The spawn radius is atleast as big as the screen height multiplied by this number
     pub const SPAWN_RADIUS_SCALER: f32 = 20.0; // This number multiplied by the wave number is added to the base spawn radius
+
+    pub const BOSS_WAVE_INTERVAL: u32 = 5; // Every this many waves a boss spawns instead of the normal enemies
+
+    // Enemies in generated waves spawn in bursts spread over the start of the wave
+    pub const SPAWN_BURST_SIZE: usize = 3; // Number of enemies that spawn together
+    pub const SPAWN_TIME: f32 = 15.0; // Seconds after the wave starts that the last burst spawns
+    pub const SPAWN_RATE_CURVE: f32 = 0.7; // Burst times follow this power of how far through the bursts the wave is, less than 1 makes bursts come closer together as the wave goes on
+    pub const MAX_ALIVE_ENEMIES: usize = 40; // Queued enemies wait to spawn while this many enemies are alive
 }
 
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
@@ -42,23 +60,78 @@ pub enum GameState {
 #[derive(Component)]
 pub struct GameComponent;
 
+// Systems that start a new run in OnEnter(AppState::GameSetup), a resumed run is restored after them
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GameSetupSystem;
+
 #[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
 pub enum WaveState {
     #[default]
     Start,
     Fight,
+    Reward, // The player picks a reward after every wave, before the countdown to the next one starts
 }
 
-#[derive(Event)]
-pub struct ScoreIncrease;
+// Sent for everything that gives points, see game::score
+#[derive(Event, Debug, Clone, Copy)]
+pub enum ScoreIncrease {
+    EnemyKilled {
+        enemy_type: usize,
+    },
+    WaveCleared,
+    CollectiblePickedUp,
+}
+
+// The area the game is played in, gameplay uses this instead of the window so the game can run headless
+// Kept the same size as the primary window when there is one
+#[derive(Resource, Debug)]
+pub struct Arena {
+    pub width: f32,
+    pub height: f32,
+    pub cursor_position: Option<Vec3>, // Cursor position in world coordinates, None if the cursor is outside the arena
+}
+
+impl Default for Arena {
+    fn default() -> Self {
+        Arena {
+            width: 1920.0,
+            height: 1080.0,
+            cursor_position: None,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct WaveCountdown {
+    pub timer: Timer,
+    pub seconds_left: u8,
+}
+
+// Gameplay systems run in FixedUpdate at this many ticks per second
+#[derive(Resource, Debug)]
+pub struct TickRate(pub f64);
+
+impl Default for TickRate {
+    fn default() -> Self {
+        TickRate(TICK_RATE)
+    }
+}
 
 #[derive(Resource, Debug)]
 pub struct Difficulty { // Containts difficulty information for the current wave
     pub wave: u32,
-    pub enemy_spawn_chance: [u8; characters::ENEMY_TYPES], // Enemy spawn chance by enemy type, order if this array is the same as the one from characters::EnemyTypes
+    pub enemy_spawn_chance: Vec<u8>, // Enemy spawn chance by enemy type, order if this vec is the same as the one from characters::EnemyTypes
     pub enemies: f32, // Number of enemies to spawn in the wave, enemies have spawn chances so slightly less enemies may be spawned
 }
 
+impl Difficulty {
+
+    // The last wave of every BOSS_WAVE_INTERVAL waves is a boss wave
+    pub fn boss_wave(&self) -> bool {
+        (self.wave + 1).is_multiple_of(difficulty_settings::BOSS_WAVE_INTERVAL)
+    }
+}
+
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
@@ -67,33 +140,134 @@ impl Plugin for GamePlugin {
         .add_event::<ScoreIncrease>()
         .init_state::<GameState>()
         .init_state::<WaveState>()
+        .init_resource::<Arena>()
+        .init_resource::<TickRate>()
+        .init_resource::<waves::WaveScript>()
+        .insert_resource(WaveCountdown {
+            timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+            seconds_left: WAVE_COUNTDOWN_SECONDS,
+        })
 
-        .add_plugins((player::PlayerPlugin, enemies::EnemyPlugin, projectiles::ProjectilePlugin, characters::CharacterPlugin, summons::SummonPlugin, damage::DamagePlugin, collectibles::CollectiblePlugin, animation::AnimationPlugin))
+        .add_plugins((player::PlayerPlugin, enemies::EnemyPlugin, projectiles::ProjectilePlugin, content::ContentPlugin, rng::RngPlugin, input::PlayerInputPlugin, replay::ReplayPlugin, interpolation::InterpolationPlugin, spatial::SpatialPlugin, rewards::RewardPlugin, summons::SummonPlugin, damage::DamagePlugin, collectibles::CollectiblePlugin, animation::AnimationPlugin))
+        .add_plugins((score::ScorePlugin, high_scores::HighScorePlugin, save::SavePlugin, keybinds::KeybindPlugin, gamepad::GamepadPlugin))
 
-        .add_systems(Update, (next_wave, dead_summons_end_game).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)))
-        .add_systems(OnExit(WaveState::Fight), update_difficulty) // Update difficulty inbetween waves
+        .add_systems(FixedUpdate, (next_wave, dead_summons_end_game).after(spatial::SpatialGridSystem).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)))
+        .add_systems(OnExit(WaveState::Fight), update_difficulty.after(rewards::offer_rewards)) // Update difficulty inbetween waves, after the rewards for the wave are picked
+        .add_systems(OnEnter(WaveState::Start), reset_wave_countdown)
+        .add_systems(FixedUpdate, wave_countdown.run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Start)).run_if(in_state(GameState::Running)))
+        .add_systems(PreUpdate, (update_arena, apply_tick_rate.run_if(resource_changed::<TickRate>)))
+        .add_systems(Update, toggle_pause.run_if(in_state(AppState::Game)))
+        .add_systems(OnEnter(GameState::Paused), pause_time)
+        .add_systems(OnExit(GameState::Paused), unpause_time)
 
-        .add_systems(OnEnter(AppState::GameSetup), init_game)
+        .add_systems(OnEnter(AppState::GameSetup), init_game.in_set(GameSetupSystem))
         .add_systems(OnEnter(AppState::GameCleanup), despawn_game_components);
     }
 }
 
-// Goes to the next wave once all the enemies are dead
+// Keeps the arena the same size as the primary window
+fn update_arena(window_query: Query<&Window, With<PrimaryWindow>>, mut arena: ResMut<Arena>) {
+    if let Ok(window) = window_query.get_single() {
+        arena.width = window.width();
+        arena.height = window.height();
+
+        // Window coordinates start at the top left, world coordinates start at the bottom left
+        arena.cursor_position = window.cursor_position().map(|position| Vec3::new(position.x, (window.height() - position.y).abs(), 0.0));
+    }
+}
+
+fn apply_tick_rate(tick_rate: Res<TickRate>, mut fixed_time: ResMut<Time<Fixed>>) {
+    fixed_time.set_timestep_hz(tick_rate.0);
+}
+
+// Toggle pause when the pause key is pressed
+fn toggle_pause(
+    player_input: Res<input::PlayerInput>,
+    game_state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if player_input.pause {
+        let state = match game_state.get() {
+            GameState::Paused => GameState::Running,
+            GameState::Running => GameState::Paused,
+            GameState::GameOver => GameState::GameOver,
+        };
+
+        next_state.set(state);
+    }
+}
+
+// Gameplay timers tick on virtual time, so they stop while the game is paused
+fn pause_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
+}
+
+fn unpause_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+fn reset_wave_countdown(mut wave_countdown: ResMut<WaveCountdown>) {
+    wave_countdown.timer.reset();
+    wave_countdown.seconds_left = WAVE_COUNTDOWN_SECONDS;
+}
+
+// Counts down between waves and changes WaveState once the countdown has finished
+fn wave_countdown(
+    mut wave_countdown: ResMut<WaveCountdown>,
+    time: Res<Time>,
+    mut next_wave_state: ResMut<NextState<WaveState>>,
+) {
+    wave_countdown.timer.tick(time.delta());
+    if wave_countdown.timer.just_finished() {
+
+        // Once the countdown has reached 0 transition to WaveState::Fight
+        if wave_countdown.seconds_left == 0 {
+            next_wave_state.set(WaveState::Fight);
+            return;
+        }
+
+        wave_countdown.seconds_left -= 1;
+    }
+}
+
+// Ends the wave once all the enemies have spawned and are dead
+// Enemies summoned by a boss don't need to be killed, they are despawned once the boss is dead
 fn next_wave(
-    enemy_query: Query<&enemies::Enemy>,
-    mut next_wave_state: ResMut<NextState<WaveState>>
+    mut commands: Commands,
+    enemy_query: Query<(), (With<enemies::Enemy>, Without<enemies::BossAdd>)>,
+    add_query: Query<Entity, With<enemies::BossAdd>>,
+    spawn_queue: Res<enemies::SpawnQueue>,
+    mut next_wave_state: ResMut<NextState<WaveState>>,
+    mut score_increase_events: EventWriter<ScoreIncrease>,
 ) {
-    if enemy_query.is_empty() {
-        next_wave_state.set(WaveState::Start);
+    // Several fixed steps can run before the state changes, the wave only ends once
+    if next_wave_state.0.is_some() {
+        return;
+    }
+
+    if enemy_query.is_empty() && spawn_queue.spawns.is_empty() {
+        for entity in add_query.iter() {
+            commands.entity(entity).despawn();
+        }
+
+        next_wave_state.set(WaveState::Reward);
+        score_increase_events.send(ScoreIncrease::WaveCleared);
     }
 }
 
-// Ends the game if all your summons die
+// Ends the game if all your summons die, and the player can't buy another one
 fn dead_summons_end_game(
     character_query: Query<&summons::Summon>,
+    player_query: Query<&player::Player>,
+    summon_types: Res<characters::SummonTypes>,
     mut next_game_state: ResMut<NextState<GameState>>
 ) {
-    if character_query.is_empty() {
+    let can_buy_summon = match player_query.get_single() {
+        Ok(player) => !player.sentries.is_empty() || summon_types.0.iter().any(|summon_info| summon_info.affordable(&player.collectibles)),
+        Err(_) => false,
+    };
+
+    if character_query.is_empty() && !can_buy_summon {
         next_game_state.set(GameState::GameOver)
     }
 }
@@ -105,16 +279,14 @@ fn update_difficulty(mut difficulty: ResMut<Difficulty>) {
     let mut trickle_ammount = difficulty_settings::MIN_TRICKLE_AMMOUNT;
     let enemy_spawn_chance = &mut difficulty.enemy_spawn_chance;
 
-    let max_value = max_value(enemy_spawn_chance.to_vec()).unwrap();
+    let max_value = max_value(enemy_spawn_chance.clone()).unwrap();
 
     // Trickle spawn rates from easier enemies into harder enemies
     for i in (0..enemy_spawn_chance.len()).rev() {
-        if enemy_spawn_chance[i] > max_value / 2 && i != enemy_spawn_chance.len() - 1 {
-            if enemy_spawn_chance[i] >= trickle_ammount {
-                enemy_spawn_chance[i] -= trickle_ammount;
-                enemy_spawn_chance[i + 1] += trickle_ammount;
-                trickle_ammount *= 2;
-            }
+        if enemy_spawn_chance[i] > max_value / 2 && i != enemy_spawn_chance.len() - 1 && enemy_spawn_chance[i] >= trickle_ammount {
+            enemy_spawn_chance[i] -= trickle_ammount;
+            enemy_spawn_chance[i + 1] += trickle_ammount;
+            trickle_ammount *= 2;
         }
     }
 
@@ -141,17 +313,16 @@ fn max_value(values: Vec<u8>) -> Result<u8, ()> {
 
 // Game resources are re-inserted every time the game is setup
 // So the score, currency, and wave always start at 0
-fn init_game(mut commands: Commands, mut next_wave_state: ResMut<NextState<WaveState>>) {
+fn init_game(mut commands: Commands, enemy_types: Res<characters::EnemyTypes>, mut next_wave_state: ResMut<NextState<WaveState>>) {
 
-    let mut enemy_spawn_chance_vec = vec![100];
-    for _ in 0..characters::ENEMY_TYPES - 1 {
-        enemy_spawn_chance_vec.push(0);
-    }
+    // Only the easiest enemy spawns on the first wave, bosses don't have a spawn chance
+    let mut enemy_spawn_chance_vec = vec![0; enemy_types.regular()];
+    enemy_spawn_chance_vec[0] = 100;
 
     commands.insert_resource(
         Difficulty {
             wave: 0,
-            enemy_spawn_chance: enemy_spawn_chance_vec.try_into().unwrap(),
+            enemy_spawn_chance: enemy_spawn_chance_vec,
             enemies: difficulty_settings::STARTING_ENEMIES,
         }
     );
@@ -1,22 +1,39 @@
 use bevy::prelude::*;
-use bevy::window::PrimaryWindow;
+use serde::Deserialize;
 use std::f32::consts::PI;
+use std::time::Duration;
 use crate::{AppState, art};
-use super::{enemies, characters, keybinds, hitboxes, projectiles, helpers, GameState, WaveState, difficulty_settings};
+use super::{characters, helpers, input::PlayerInput, interpolation, projectiles, spatial, GameState, WaveState, Arena, difficulty_settings};
 
 #[derive(Component)]
 pub struct Player {
     pub health: i32,
+    pub max_health: i32,
     speed: f32, // Current speed 
     direction_vector: Vec3, // Normaliszed direction vector
     movement_keys_pressed: bool, // True if any of the movement keys are pressed
-    pub collectibles: [i32; characters::COLLECTIBLE_TYPES], // Different types of currencies the player has collected
+    pub collectibles: Vec<i32>, // Different types of currencies the player has collected, indexed the same as characters::CollectibleTypes
+    pub sentries: Vec<usize>, // Sentries that are ready to be placed, by summon type index
+    attack_cooldown: f32, // Seconds until the player can attack again
+    pub dash_cooldown: f32, // Seconds until the player can dash again
+    dash_held: bool, // True if the dash key was held last tick, so holding it only dashes once
+}
+
+// The player attack is defined in the content file, see game::content
+#[derive(Resource, Clone, Debug, Deserialize)]
+pub struct PlayerAttackInfo {
+    pub projectile_types_index: usize, // Projectile fired towards the cursor, it's damage, speed and range are set in the projectile
+    pub cooldown: f32, // Seconds between attacks
 }
 
 const PLAYER_SPEED: f32 = 400.0; // Max speed
 const PLAYER_MAX_HEALTH: i32 = 3;
 const PLAYER_ACCELERATION: f32 = 8000.0; // Player acceleration and deacceleration
 
+pub const DASH_DISTANCE: f32 = 200.0; // Distance the player blinks in the direction they are moving
+pub const DASH_COOLDOWN: f32 = 2.5; // Seconds between dashes
+const DASH_INVULNARABILITY: f32 = 0.3; // Seconds the player can't be hit for after dashing
+
 #[derive(Resource)]
 struct HitInvulnaribilityTimer(Timer);
 
@@ -37,9 +54,11 @@ impl Plugin for PlayerPlugin {
         app
             .insert_resource(HitInvulnaribilityTimer(Timer::from_seconds(1.0, TimerMode::Once)))
             .insert_resource(PlayerAnimationTimer(Timer::from_seconds(1.0 / art::ANIMATION_FPS, TimerMode::Repeating)))
-            .add_systems(OnEnter(AppState::GameSetup), spawn_player)
+            .add_systems(OnEnter(AppState::GameSetup), spawn_player.in_set(super::GameSetupSystem))
             .add_systems(OnEnter(WaveState::Start), heal_player) // Heal player at the start of every wave
-            .add_systems(Update, (move_player, collisions, check_for_game_over, select_animation, animate_player).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+            .add_systems(FixedUpdate, (move_player.before(spatial::SpatialGridSystem), dash.after(move_player).before(spatial::SpatialGridSystem), collisions.after(spatial::SpatialGridSystem), check_for_game_over).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)))
+            .add_systems(FixedUpdate, attack.after(spatial::SpatialGridSystem).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)))
+            .add_systems(Update, (select_animation, animate_player.after(interpolation::interpolate_translations)).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
     }
 }
 
@@ -110,11 +129,11 @@ fn select_animation(
     }
 }
 
-fn heal_player(
+pub fn heal_player(
     mut player_query: Query<&mut Player>
 ) {
     if let Ok(mut player) = player_query.get_single_mut() {
-        player.health = PLAYER_MAX_HEALTH;
+        player.health = player.max_health;
     }
 }
 
@@ -132,14 +151,19 @@ fn check_for_game_over(
 
 fn spawn_player(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    arena: Res<Arena>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    collectible_types: Res<characters::CollectibleTypes>,
     asset_server: Res<AssetServer>
 ) {
-    let window = window_query.get_single().unwrap();
+    // Starting collectibles that aren't defined in the difficulty settings start at 0
+    let mut collectibles = vec![0; collectible_types.0.len()];
+    for (collectible, starting_ammount) in collectibles.iter_mut().zip(difficulty_settings::STARTING_COLLECTIBLES) {
+        *collectible = *starting_ammount;
+    }
 
-    let move_texture: Handle<Image> = asset_server.load(art::PLAYER_MOVE.spritesheet_path);
-    let idle_texture: Handle<Image> = asset_server.load(art::PLAYER_IDLE.spritesheet_path);
+    let move_texture: Handle<Image> = asset_server.load(art::PLAYER_MOVE.spritesheet_path.to_string());
+    let idle_texture: Handle<Image> = asset_server.load(art::PLAYER_IDLE.spritesheet_path.to_string());
 
     let move_layout = TextureAtlasLayout::from_grid(
         art::PLAYER_MOVE.sprite_size,
@@ -159,17 +183,24 @@ fn spawn_player(
     );
     let idle_layout = texture_atlas_layouts.add(idle_layout);
     
+    let player_translation = Vec3::new(arena.width / 2.0, arena.height / 2.0, 0.0);
     commands.spawn(
         (
             super::GameComponent,
             Player {
                 health: PLAYER_MAX_HEALTH,
+                max_health: PLAYER_MAX_HEALTH,
                 speed: 0.0,
                 direction_vector: Vec3::splat(0.0),
                 movement_keys_pressed: false,
-                collectibles: difficulty_settings::STARTING_COLLECTIBLES,
+                collectibles,
+                sentries: Vec::new(),
+                attack_cooldown: 0.0,
+                dash_cooldown: 0.0,
+                dash_held: false,
             },
-            Transform::from_xyz(window.width() / 2.0, window.height() / 2.0, 0.0),
+            Transform::from_translation(player_translation),
+            interpolation::Interpolated::new(player_translation),
         )
     );
 
@@ -221,28 +252,29 @@ fn spawn_player(
 // Move player based off player inputs
 fn move_player(
     mut player_query: Query<(&mut Transform, &mut Player)>,
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
+    arena: Res<Arena>,
+    player_input: Res<PlayerInput>,
     time: Res<Time>,
 ) {
-    let window = window_query.get_single().unwrap();
-
     let (mut player_transform, mut player) = player_query.get_single_mut().unwrap();
 
     // Movement vector based on the keys the player is pressing
     let mut direction_vector = Vec3::splat(0.0);
 
     let mut movement_keys_pressed = false;
-    if keyboard_input.pressed(keybinds::PLAYER_UP) {
+    if player_input.up {
         movement_keys_pressed = true;
         direction_vector.y += 1.0;
-    } if keyboard_input.pressed(keybinds::PLAYER_LEFT) {
+    }
+    if player_input.left {
         movement_keys_pressed = true;
         direction_vector.x -= 1.0;
-    } if keyboard_input.pressed(keybinds::PLAYER_DOWN) {
+    }
+    if player_input.down {
         movement_keys_pressed = true;
         direction_vector.y -= 1.0;
-    } if keyboard_input.pressed(keybinds::PLAYER_RIGHT) {
+    }
+    if player_input.right {
         movement_keys_pressed = true;
         direction_vector.x += 1.0;
     }
@@ -270,7 +302,7 @@ fn move_player(
     let speed_multiplier = player.speed * time.delta_seconds();
     let new_position = player_transform.translation + player.direction_vector * speed_multiplier;
     
-    if helpers::is_on_screen(&new_position, &window) {
+    if helpers::is_on_screen(&new_position, &arena) {
         player_transform.translation = new_position;
     } else {
         player.direction_vector = helpers::vector_multiply(&direction_vector, &Vec3::new(-1.0, -1.0, 0.0));
@@ -279,14 +311,94 @@ fn move_player(
 
 }
 
+// Blinks the player in the direction they are moving, stopping at the edge of the screen
+// The player can't be hit for a moment after dashing, using the same timer as getting hit
+fn dash(
+    mut player_query: Query<(&mut Transform, &mut Player)>,
+    mut invulnarability_timer: ResMut<HitInvulnaribilityTimer>,
+    arena: Res<Arena>,
+    player_input: Res<PlayerInput>,
+    time: Res<Time>,
+) {
+    let (mut player_transform, mut player) = player_query.get_single_mut().unwrap();
+
+    player.dash_cooldown = (player.dash_cooldown - time.delta_seconds()).max(0.0);
+
+    let dash_pressed = player_input.dash && !player.dash_held;
+    player.dash_held = player_input.dash;
+    if !dash_pressed || player.dash_cooldown > 0.0 || player.direction_vector == Vec3::ZERO {
+        return;
+    }
+
+    let new_position = player_transform.translation + player.direction_vector * DASH_DISTANCE;
+    player_transform.translation = helpers::clamp_to_screen(&new_position, &arena);
+    player.dash_cooldown = DASH_COOLDOWN;
+
+    // Don't shorten invulnarability the player already has from getting hit
+    let timer = &mut invulnarability_timer.0;
+    if timer.remaining_secs() < DASH_INVULNARABILITY {
+        let elapsed = timer.duration().saturating_sub(Duration::from_secs_f32(DASH_INVULNARABILITY));
+        timer.reset();
+        timer.set_elapsed(elapsed);
+    }
+}
+
+// Fires the player attack towards the cursor while the attack key is held
+fn attack(
+    mut commands: Commands,
+    mut player_query: Query<(&Transform, &mut Player, Entity)>,
+    player_input: Res<PlayerInput>,
+    player_attack: Res<PlayerAttackInfo>,
+    projectile_types: Res<projectiles::ProjectileTypes>,
+    asset_server: Res<AssetServer>,
+    time: Res<Time>,
+) {
+    let (player_transform, mut player, player_entity) = player_query.get_single_mut().unwrap();
+
+    player.attack_cooldown = (player.attack_cooldown - time.delta_seconds()).max(0.0);
+    if !player_input.attack || player.attack_cooldown > 0.0 {
+        return;
+    }
+
+    let Some(cursor_position) = player_input.cursor_position else {
+        return;
+    };
+    let direction_vector = (cursor_position - player_transform.translation).normalize_or_zero();
+    if direction_vector == Vec3::ZERO {
+        return;
+    }
+
+    player.attack_cooldown = player_attack.cooldown;
+
+    let projectile_types_index = player_attack.projectile_types_index;
+    commands.spawn(
+        (
+            projectiles::Projectile {
+                direction_vector,
+                projectile_types_index,
+                distance: 0.0,
+                shooter: player_entity,
+            },
+            super::GameComponent,
+            interpolation::Interpolated::new(player_transform.translation),
+            SpriteBundle {
+                transform: Transform {
+                    translation: player_transform.translation,
+                    scale: Vec3::splat(art::SPRITE_SCALE),
+                    ..default()
+                },
+                texture: asset_server.load(projectile_types.0[projectile_types_index].sprite_information.sprite_path.to_string()),
+                ..default()
+            }
+        )
+    );
+}
+
 // Handle collisions between player and enemies, and enemy projectiles
 fn collisions(
     mut commands: Commands,
     mut player_query: Query<(&Transform, &mut Player)>,
-    enemy_query: Query<(&Transform, &characters::Character), With<enemies::Enemy>>,
-    projectile_query: Query<(&Transform, &projectiles::Projectile, Entity)>,
-    projectile_types: Res<projectiles::ProjectileTypes>,
-    enemy_types: Res<characters::EnemyTypes>,
+    spatial_grid: Res<spatial::SpatialGrid>,
     mut invulnarability_timer: ResMut<HitInvulnaribilityTimer>,
     time: Res<Time>,
 ) {
@@ -295,24 +407,15 @@ fn collisions(
 
     let mut collision = false;
 
-    // Test for contact collisions with enemies
-    for (enemy_transform, enemy) in enemy_query.iter() {
-        let enemy_hitbox = enemy_types.0[enemy.type_index].animation_information.hitbox;
-
-        if hitboxes::are_hitboxes_colliding(&art::PLAYER_HITBOX, &player_transfrom.translation, &enemy_hitbox, &enemy_transform.translation) {
-            collision = true;
-        }
-    }
-
-    // Test for projectile collisions
-    for (projectile_transform, projectile, entity) in projectile_query.iter() {
-        let projectile_type = &projectile_types.0[projectile.projectile_types_index];
-
-        if projectile_type.enemy { // Only damage the player if it is an enemy projectile
-            if hitboxes::are_hitboxes_colliding(&art::PLAYER_HITBOX, &player_transfrom.translation, &projectile_type.sprite_information.hitbox, &projectile_transform.translation) {
-                commands.entity(entity).despawn();
+    // Test for contact collisions with enemies, and enemy projectile collisions
+    for entry in spatial_grid.colliding(&art::PLAYER_HITBOX, &player_transfrom.translation) {
+        match entry.kind {
+            spatial::SpatialKind::Enemy => collision = true,
+            spatial::SpatialKind::EnemyProjectile => {
+                commands.entity(entry.entity).despawn();
                 collision = true;
-            }
+            },
+            _ => (), // Only enemies and enemy projectiles damage the player
         }
     }
 
@@ -1,19 +1,27 @@
 use bevy::prelude::*;
+use serde::Deserialize;
 use crate::{AppState, art, game};
-use bevy::window::PrimaryWindow;
-use game::{GameState, helpers, projectiles};
+use game::{GameState, Arena, helpers, projectiles, spatial};
 
 #[derive(Component)]
 pub struct Projectile {
     pub direction_vector: Vec3,
     pub projectile_types_index: usize, // Index of bullet in ProjectileTypes resource
+    pub distance: f32, // Distance travelled so far
+    pub shooter: Entity, // Character that fired the projectile, enemies hit by it build up threat towards the shooter
 }
 
+// Projectiles are defined in the content file, see game::content
+#[derive(Clone, Debug, Deserialize)]
 pub struct ProjectileInfo {
+    pub name: String,
     pub damage: i32,
     pub speed: f32,
     pub piercing: bool,
     pub enemy: bool,
+
+    #[serde(default)]
+    pub range: Option<f32>, // Distance the projectile travels before it despawns, it travels until it leaves the screen if there is no range
     pub sprite_information: art::SpriteInformation,
 }
 
@@ -25,79 +33,27 @@ pub struct ProjectilePlugin;
 impl Plugin for ProjectilePlugin {
     fn build(&self, app: &mut App) {
         app
-        .insert_resource(ProjectileTypes(
-            vec![
-                // Enemy projectiles
-                ProjectileInfo {
-                    damage: 2,
-                    speed: 250.0,
-                    piercing: false,
-                    enemy: true,
-                    sprite_information: art::ENEMY_PROJECTILE_SPRITE,
-                },
-                
-                ProjectileInfo {
-                    damage: 3,
-                    speed: 280.0,
-                    piercing: false,
-                    enemy: true,
-                    sprite_information: art::ENEMY_PROJECTILE_SPRITE,
-                },
-
-                ProjectileInfo {
-                    damage: 4,
-                    speed: 300.0,
-                    piercing: false,
-                    enemy: true,
-                    sprite_information: art::ENEMY_PROJECTILE_SPRITE,
-                },
-
-                ProjectileInfo {
-                    damage: 8,
-                    speed: 330.0,
-                    piercing: false,
-                    enemy: true,
-                    sprite_information: art::ENEMY_PROJECTILE_SPRITE,
-                },
-
-                // Friendly projectiles
-                ProjectileInfo {
-                    damage: 1,
-                    speed: 350.0,
-                    piercing: false,
-                    enemy: false,
-                    sprite_information: art::FRIENDLY_PROJECTILE_1_SPRITE,
-                },
-
-                ProjectileInfo {
-                    damage: 2,
-                    speed: 400.0,
-                    piercing: true,
-                    enemy: false,
-                    sprite_information: art::FRIENDLY_PROJECTILE_2_SPRITE,
-                },
-            ]
-        ))
-        .add_systems(Update, move_projectiles.run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+        .add_systems(FixedUpdate, move_projectiles.before(spatial::SpatialGridSystem).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
         
     }
 }
 
-// Moves all projectiles, and despawns them when they go off screen
+// Moves all projectiles, and despawns them when they go off screen or out of range
 fn move_projectiles(
     mut commands: Commands,
-    mut projectile_query: Query<(&mut Transform, &projectiles::Projectile, Entity)>,
+    mut projectile_query: Query<(&mut Transform, &mut projectiles::Projectile, Entity)>,
     projectile_types: Res<projectiles::ProjectileTypes>,
     time: Res<Time>,
-    window_query: Query<&Window, With<PrimaryWindow>>
+    arena: Res<Arena>,
 ) {
-    let window = window_query.get_single().unwrap();
-
-    for (mut transform, projectile, entity) in projectile_query.iter_mut() {
+    for (mut transform, mut projectile, entity) in projectile_query.iter_mut() {
         let projectile_info = &projectile_types.0[projectile.projectile_types_index];
-        transform.translation += projectile.direction_vector * projectile_info.speed * time.delta_seconds();
+        let distance = projectile_info.speed * time.delta_seconds();
+        transform.translation += projectile.direction_vector * distance;
+        projectile.distance += distance;
 
-        if !helpers::is_on_screen(&transform.translation, &window) {
+        let out_of_range = projectile_info.range.is_some_and(|range| projectile.distance > range);
+        if !helpers::is_on_screen(&transform.translation, &arena) || out_of_range {
             commands.entity(entity).despawn();
         }
     }
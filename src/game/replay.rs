@@ -0,0 +1,632 @@
+use bevy::prelude::*;
+use bevy::time::{TimeSystem, TimeUpdateStrategy};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fmt, fs, io};
+
+use crate::AppState;
+use super::{input::{PlayerInput, PlayerInputSystem}, rng::{GameSeed, SeedSettings}, enemies::Enemy, player::Player, summons::AutoSpend, Difficulty, GameState};
+
+// Records the player input and frame time of a run so it can be played back exactly
+// While a replay plays the recorded health, wave and enemy count are checked against the run
+
+const REPLAY_MAGIC: &[u8; 4] = b"HHRP";
+pub const REPLAY_VERSION: u16 = 8;
+
+// Input flags, one bit per input except the reward choice which takes three
+const UP_FLAG: u32 = 1 << 0;
+const DOWN_FLAG: u32 = 1 << 1;
+const LEFT_FLAG: u32 = 1 << 2;
+const RIGHT_FLAG: u32 = 1 << 3;
+const MOVE_RANGED_FLAG: u32 = 1 << 4;
+const MOVE_MELEE_FLAG: u32 = 1 << 5;
+const PAUSE_TOGGLED_FLAG: u32 = 1 << 6;
+const CURSOR_FLAG: u32 = 1 << 7;
+const PLACE_SENTRY_FLAG: u32 = 1 << 8;
+const REWARD_CHOICE_SHIFT: u32 = 9; // The reward choice plus one is stored in the bits from here, 0 if no reward was chosen
+const REWARD_CHOICE_MASK: u32 = 0b111;
+const BUY_SUMMON_FLAG: u32 = 1 << 12;
+const BUY_SUMMON_CLICKED_FLAG: u32 = 1 << 13;
+const ATTACK_FLAG: u32 = 1 << 14;
+const DASH_FLAG: u32 = 1 << 15;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReplayFrame {
+    pub input: PlayerInput,
+    pub delta: Duration, // Frame time, stored exactly so the playback gets the same floating point results
+    pub pause_toggled: bool, // True if the game was paused or unpaused this frame
+}
+
+// Values compared between the recording and the playback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RunCheckpoint {
+    pub player_health: i32,
+    pub wave: u32,
+    pub enemies: u32,
+}
+
+// Replay file layout, all numbers are little endian
+// Header: magic, version (u16), seed (u64), auto spend (u8), frame count (u32), checkpoint count (u32)
+// Frames: input flags (u32), frame time in nanoseconds (u32), cursor x and y (f32) only when the cursor flag is set, summon bought (u16) only when the buy summon flag is set
+// Checkpoints are only stored when a value changes: frame (u32), player health (i32), wave (u32), enemies (u32)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub auto_spend: bool, // Summons were bought automatically during the run
+    pub frames: Vec<ReplayFrame>,
+    pub checkpoints: Vec<(u32, RunCheckpoint)>,
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Invalid(&'static str),
+    RewardChoice(usize), // Reward choice too large for the bits it is stored in
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "could not access replay file: {}", error),
+            ReplayError::Invalid(reason) => write!(f, "invalid replay file: {}", reason),
+            ReplayError::RewardChoice(reward_choice) => write!(f, "reward choice {} is too large to store in a replay", reward_choice),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+// First difference between a replay and the run played from it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayMismatch {
+    pub frame: u32,
+    pub field: &'static str,
+    pub recorded: i64,
+    pub actual: i64,
+}
+
+impl fmt::Display for ReplayMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "frame {}: {} was {} in the recording but is {}", self.frame, self.field, self.recorded, self.actual)
+    }
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Replay, ReplayError> {
+        Replay::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ReplayError> {
+        fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ReplayError> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.extend_from_slice(&REPLAY_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&self.seed.to_le_bytes());
+        bytes.push(self.auto_spend as u8);
+        bytes.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.checkpoints.len() as u32).to_le_bytes());
+
+        for frame in self.frames.iter() {
+            let input = &frame.input;
+            let mut flags = 0;
+            for (pressed, flag) in [
+                (input.up, UP_FLAG),
+                (input.down, DOWN_FLAG),
+                (input.left, LEFT_FLAG),
+                (input.right, RIGHT_FLAG),
+                (input.move_ranged_summons, MOVE_RANGED_FLAG),
+                (input.move_melee_summons, MOVE_MELEE_FLAG),
+                (input.attack, ATTACK_FLAG),
+                (input.dash, DASH_FLAG),
+                (frame.pause_toggled, PAUSE_TOGGLED_FLAG),
+                (input.cursor_position.is_some(), CURSOR_FLAG),
+                (input.place_sentry, PLACE_SENTRY_FLAG),
+                (input.buy_summon.is_some(), BUY_SUMMON_FLAG),
+                (input.buy_summon_clicked, BUY_SUMMON_CLICKED_FLAG),
+            ] {
+                if pressed {
+                    flags |= flag;
+                }
+            }
+
+            if let Some(reward_choice) = input.reward_choice {
+                if reward_choice as u32 + 1 > REWARD_CHOICE_MASK {
+                    return Err(ReplayError::RewardChoice(reward_choice));
+                }
+                flags |= (reward_choice as u32 + 1) << REWARD_CHOICE_SHIFT;
+            }
+
+            bytes.extend_from_slice(&flags.to_le_bytes());
+            bytes.extend_from_slice(&(frame.delta.as_nanos().min(u32::MAX as u128) as u32).to_le_bytes());
+            if let Some(cursor_position) = input.cursor_position {
+                bytes.extend_from_slice(&cursor_position.x.to_le_bytes());
+                bytes.extend_from_slice(&cursor_position.y.to_le_bytes());
+            }
+            if let Some(buy_summon) = input.buy_summon {
+                bytes.extend_from_slice(&(buy_summon as u16).to_le_bytes());
+            }
+        }
+
+        for (frame, checkpoint) in self.checkpoints.iter() {
+            bytes.extend_from_slice(&frame.to_le_bytes());
+            bytes.extend_from_slice(&checkpoint.player_health.to_le_bytes());
+            bytes.extend_from_slice(&checkpoint.wave.to_le_bytes());
+            bytes.extend_from_slice(&checkpoint.enemies.to_le_bytes());
+        }
+
+        Ok(bytes)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Replay, ReplayError> {
+        let mut reader = ByteReader { bytes, position: 0 };
+
+        if reader.take(4)? != REPLAY_MAGIC {
+            return Err(ReplayError::Invalid("not a replay file"));
+        }
+        if reader.u16()? != REPLAY_VERSION {
+            return Err(ReplayError::Invalid("unsupported replay version"));
+        }
+
+        let seed = reader.u64()?;
+        let auto_spend = reader.take(1)?[0] != 0;
+        let frame_count = reader.u32()?;
+        let checkpoint_count = reader.u32()?;
+
+        let mut frames = Vec::new();
+        for _ in 0..frame_count {
+            let flags = reader.u32()?;
+            let delta = Duration::from_nanos(reader.u32()? as u64);
+            let cursor_position = if flags & CURSOR_FLAG != 0 {
+                Some(Vec3::new(reader.f32()?, reader.f32()?, 0.0))
+            } else {
+                None
+            };
+            let buy_summon = if flags & BUY_SUMMON_FLAG != 0 {
+                Some(reader.u16()? as usize)
+            } else {
+                None
+            };
+
+            frames.push(ReplayFrame {
+                input: PlayerInput {
+                    up: flags & UP_FLAG != 0,
+                    down: flags & DOWN_FLAG != 0,
+                    left: flags & LEFT_FLAG != 0,
+                    right: flags & RIGHT_FLAG != 0,
+                    move_ranged_summons: flags & MOVE_RANGED_FLAG != 0,
+                    move_melee_summons: flags & MOVE_MELEE_FLAG != 0,
+                    attack: flags & ATTACK_FLAG != 0,
+                    dash: flags & DASH_FLAG != 0,
+                    pause: false,
+                    place_sentry: flags & PLACE_SENTRY_FLAG != 0,
+                    reward_choice: ((flags >> REWARD_CHOICE_SHIFT) & REWARD_CHOICE_MASK).checked_sub(1).map(|choice| choice as usize),
+                    buy_summon,
+                    buy_summon_clicked: flags & BUY_SUMMON_CLICKED_FLAG != 0,
+                    cursor_position,
+                },
+                delta,
+                pause_toggled: flags & PAUSE_TOGGLED_FLAG != 0,
+            });
+        }
+
+        let mut checkpoints = Vec::new();
+        for _ in 0..checkpoint_count {
+            let frame = reader.u32()?;
+            checkpoints.push((frame, RunCheckpoint {
+                player_health: reader.i32()?,
+                wave: reader.u32()?,
+                enemies: reader.u32()?,
+            }));
+        }
+
+        if reader.position != bytes.len() {
+            return Err(ReplayError::Invalid("unexpected data after the last checkpoint"));
+        }
+
+        Ok(Replay { seed, auto_spend, frames, checkpoints })
+    }
+
+    // The recorded checkpoint that was current on the given frame
+    pub fn checkpoint_at(&self, frame: u32) -> Option<RunCheckpoint> {
+        let index = self.checkpoints.partition_point(|(checkpoint_frame, _)| *checkpoint_frame <= frame);
+        if index == 0 {
+            None
+        } else {
+            Some(self.checkpoints[index - 1].1)
+        }
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ReplayError> {
+        let bytes = self.bytes.get(self.position..self.position + length).ok_or(ReplayError::Invalid("file ends early"))?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16, ReplayError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, ReplayError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i32(&mut self) -> Result<i32, ReplayError> {
+        Ok(i32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, ReplayError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn f32(&mut self) -> Result<f32, ReplayError> {
+        Ok(f32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ReplaySettings {
+    pub record: bool, // Record every run
+    pub record_path: Option<PathBuf>, // Where recorded runs are saved
+    pub replay_path: Option<PathBuf>, // Replay to play once the game has loaded
+}
+
+// Replay waiting to be played from the main menu
+#[derive(Resource)]
+pub struct PendingReplay(pub Replay);
+
+pub struct Playback {
+    pub replay: Replay,
+    pub next_frame: usize,
+    pub mismatch: Option<ReplayMismatch>,
+    time_update_strategy: TimeUpdateStrategy, // Restored once the replay is finished
+    seed: Option<u64>, // Seed setting restored once the replay is finished
+    auto_spend: bool, // Auto spend setting restored once the replay is finished
+}
+
+#[derive(Resource, Default)]
+pub enum ReplayState {
+    #[default]
+    Idle,
+    Recording {
+        replay: Replay,
+        last_checkpoint: Option<RunCheckpoint>,
+        game_state: GameState,
+    },
+    Playing(Playback),
+    Recorded(Replay), // The last recorded run
+    Finished(Option<ReplayMismatch>), // The last replay played, with the first mismatch found
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ReplaySettings>()
+            .init_resource::<ReplayState>()
+            .add_systems(OnEnter(AppState::MainMenu), load_replay_file)
+            .add_systems(Update, start_playback.run_if(in_state(AppState::MainMenu)))
+            .add_systems(OnEnter(AppState::GameSetup), start_recording)
+            .add_systems(OnEnter(GameState::GameOver), finish_recording)
+            .add_systems(OnEnter(AppState::GameCleanup), (finish_recording, stop_playback))
+            .add_systems(First, playback_frame_time.before(TimeSystem))
+            .add_systems(PreUpdate, playback_input.after(PlayerInputSystem))
+            .add_systems(Last, (record_frame, check_playback_frame));
+    }
+}
+
+// Queues the replay given on the command line, it is only played once
+fn load_replay_file(mut commands: Commands, mut replay_settings: ResMut<ReplaySettings>) {
+    if let Some(path) = replay_settings.replay_path.take() {
+        match Replay::load(&path) {
+            Ok(replay) => commands.insert_resource(PendingReplay(replay)),
+            Err(error) => error!("Could not play replay {}: {}", path.display(), error),
+        }
+    }
+}
+
+// Starts a new run from the pending replay, the run is played with the replay's seed
+fn start_playback(
+    mut commands: Commands,
+    pending_replay: Option<Res<PendingReplay>>,
+    mut replay_state: ResMut<ReplayState>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut auto_spend: ResMut<AutoSpend>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    let Some(pending_replay) = pending_replay else {
+        return;
+    };
+    let replay = pending_replay.0.clone();
+    commands.remove_resource::<PendingReplay>();
+
+    let seed = seed_settings.seed;
+    seed_settings.seed = Some(replay.seed);
+    let last_auto_spend = auto_spend.0;
+    auto_spend.0 = replay.auto_spend;
+
+    *replay_state = ReplayState::Playing(Playback {
+        replay,
+        next_frame: 0,
+        mismatch: None,
+        time_update_strategy: std::mem::take(&mut *time_update_strategy),
+        seed,
+        auto_spend: last_auto_spend,
+    });
+    next_app_state.set(AppState::GameSetup);
+}
+
+fn start_recording(replay_settings: Res<ReplaySettings>, auto_spend: Res<AutoSpend>, mut replay_state: ResMut<ReplayState>) {
+    if replay_settings.record && !matches!(*replay_state, ReplayState::Playing(_)) {
+        *replay_state = ReplayState::Recording {
+            replay: Replay {
+                auto_spend: auto_spend.0,
+                ..default()
+            },
+            last_checkpoint: None,
+            game_state: GameState::Running,
+        };
+    }
+}
+
+// Saves the recording once the run is over
+fn finish_recording(replay_settings: Res<ReplaySettings>, mut replay_state: ResMut<ReplayState>) {
+    if let ReplayState::Recording { replay, .. } = &*replay_state {
+        if let Some(path) = &replay_settings.record_path {
+            match replay.save(path) {
+                Ok(()) => info!("Saved replay to {}", path.display()),
+                Err(error) => error!("Could not save replay {}: {}", path.display(), error),
+            }
+        }
+
+        *replay_state = ReplayState::Recorded(replay.clone());
+    }
+}
+
+// Stops a replay that is left before it has finished
+fn stop_playback(
+    mut replay_state: ResMut<ReplayState>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut auto_spend: ResMut<AutoSpend>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+) {
+    if let ReplayState::Playing(playback) = &mut *replay_state {
+        *time_update_strategy = std::mem::take(&mut playback.time_update_strategy);
+        seed_settings.seed = playback.seed;
+        auto_spend.0 = playback.auto_spend;
+        *replay_state = ReplayState::Finished(playback.mismatch);
+    }
+}
+
+// Uses the recorded frame time, must run before time is updated
+fn playback_frame_time(replay_state: Res<ReplayState>, mut time_update_strategy: ResMut<TimeUpdateStrategy>) {
+    if let ReplayState::Playing(playback) = &*replay_state {
+        if let Some(frame) = playback.replay.frames.get(playback.next_frame) {
+            *time_update_strategy = TimeUpdateStrategy::ManualDuration(frame.delta);
+        }
+    }
+}
+
+// Replaces the player input with the recorded input
+fn playback_input(
+    replay_state: Res<ReplayState>,
+    mut player_input: ResMut<PlayerInput>,
+    game_state: Res<State<GameState>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    if let ReplayState::Playing(playback) = &*replay_state {
+        if let Some(frame) = playback.replay.frames.get(playback.next_frame) {
+            *player_input = frame.input;
+
+            if frame.pause_toggled {
+                match game_state.get() {
+                    GameState::Running => next_game_state.set(GameState::Paused),
+                    GameState::Paused => next_game_state.set(GameState::Running),
+                    GameState::GameOver => (),
+                }
+            }
+        }
+    }
+}
+
+fn run_checkpoint(player_query: &Query<&Player>, difficulty: &Option<Res<Difficulty>>, enemy_query: &Query<(), With<Enemy>>) -> RunCheckpoint {
+    RunCheckpoint {
+        player_health: player_query.get_single().map(|player| player.health).unwrap_or(0),
+        wave: difficulty.as_ref().map(|difficulty| difficulty.wave).unwrap_or(0),
+        enemies: enemy_query.iter().count() as u32,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn record_frame(
+    mut replay_state: ResMut<ReplayState>,
+    player_input: Res<PlayerInput>,
+    time: Res<Time<Real>>,
+    game_seed: Option<Res<GameSeed>>,
+    game_state: Res<State<GameState>>,
+    player_query: Query<&Player>,
+    difficulty: Option<Res<Difficulty>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let ReplayState::Recording { replay, last_checkpoint, game_state: last_game_state } = &mut *replay_state else {
+        return;
+    };
+
+    if let Some(game_seed) = game_seed {
+        replay.seed = game_seed.0;
+    }
+
+    let frame = replay.frames.len() as u32;
+
+    // Pausing is recorded as the state changing, so pausing from the pause menu is recorded as well
+    let pause_toggled = matches!(
+        (*last_game_state, *game_state.get()),
+        (GameState::Running, GameState::Paused) | (GameState::Paused, GameState::Running)
+    );
+    *last_game_state = *game_state.get();
+
+    replay.frames.push(ReplayFrame {
+        input: PlayerInput {
+            pause: false,
+            ..*player_input
+        },
+        delta: time.delta(),
+        pause_toggled,
+    });
+
+    let checkpoint = run_checkpoint(&player_query, &difficulty, &enemy_query);
+    if *last_checkpoint != Some(checkpoint) {
+        replay.checkpoints.push((frame, checkpoint));
+        *last_checkpoint = Some(checkpoint);
+    }
+}
+
+// Compares the run to the recording, and finishes the replay after the last frame
+#[allow(clippy::too_many_arguments)]
+fn check_playback_frame(
+    mut replay_state: ResMut<ReplayState>,
+    mut seed_settings: ResMut<SeedSettings>,
+    mut auto_spend: ResMut<AutoSpend>,
+    mut time_update_strategy: ResMut<TimeUpdateStrategy>,
+    app_state: Res<State<AppState>>,
+    player_query: Query<&Player>,
+    difficulty: Option<Res<Difficulty>>,
+    enemy_query: Query<(), With<Enemy>>,
+) {
+    let ReplayState::Playing(playback) = &mut *replay_state else {
+        return;
+    };
+
+    // The replay is started from the main menu, recorded frames start once the game is being setup
+    if app_state.get() == &AppState::MainMenu {
+        return;
+    }
+
+    let frame = playback.next_frame as u32;
+    if playback.mismatch.is_none() {
+        if let Some(recorded) = playback.replay.checkpoint_at(frame) {
+            let actual = run_checkpoint(&player_query, &difficulty, &enemy_query);
+
+            let mismatch = if recorded.player_health != actual.player_health {
+                Some(("player health", recorded.player_health as i64, actual.player_health as i64))
+            } else if recorded.wave != actual.wave {
+                Some(("wave", recorded.wave as i64, actual.wave as i64))
+            } else if recorded.enemies != actual.enemies {
+                Some(("enemies", recorded.enemies as i64, actual.enemies as i64))
+            } else {
+                None
+            };
+
+            if let Some((field, recorded, actual)) = mismatch {
+                let mismatch = ReplayMismatch { frame, field, recorded, actual };
+                warn!("Replay does not match the recording at {}", mismatch);
+                playback.mismatch = Some(mismatch);
+            }
+        }
+    }
+
+    playback.next_frame += 1;
+    if playback.next_frame >= playback.replay.frames.len() {
+        match playback.mismatch {
+            Some(mismatch) => info!("Replay finished, first mismatch at {}", mismatch),
+            None => info!("Replay finished, the run matched the recording"),
+        }
+
+        *time_update_strategy = std::mem::take(&mut playback.time_update_strategy);
+        seed_settings.seed = playback.seed;
+        auto_spend.0 = playback.auto_spend;
+        *replay_state = ReplayState::Finished(playback.mismatch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(input: PlayerInput) -> ReplayFrame {
+        ReplayFrame {
+            input,
+            delta: Duration::from_nanos(16_666_667),
+            pause_toggled: false,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let replay = Replay {
+            seed: 0x0123_4567_89ab_cdef,
+            auto_spend: true,
+            frames: vec![
+                frame(PlayerInput::default()),
+                frame(PlayerInput {
+                    up: true,
+                    right: true,
+                    move_melee_summons: true,
+                    attack: true,
+                    dash: true,
+                    cursor_position: Some(Vec3::new(12.5, -300.25, 0.0)),
+                    ..default()
+                }),
+                frame(PlayerInput {
+                    down: true,
+                    left: true,
+                    move_ranged_summons: true,
+                    place_sentry: true,
+                    reward_choice: Some(REWARD_CHOICE_MASK as usize - 1),
+                    buy_summon: Some(3),
+                    buy_summon_clicked: true,
+                    ..default()
+                }),
+                ReplayFrame {
+                    pause_toggled: true,
+                    delta: Duration::from_millis(250),
+                    ..frame(PlayerInput { reward_choice: Some(0), ..default() })
+                },
+            ],
+            checkpoints: vec![
+                (0, RunCheckpoint { player_health: 100, wave: 0, enemies: 0 }),
+                (3, RunCheckpoint { player_health: -5, wave: 2, enemies: 17 }),
+            ],
+        };
+
+        let bytes = replay.to_bytes().unwrap();
+        assert_eq!(Replay::from_bytes(&bytes).unwrap(), replay);
+    }
+
+    #[test]
+    fn reward_choice_too_large() {
+        let replay = Replay {
+            frames: vec![frame(PlayerInput { reward_choice: Some(REWARD_CHOICE_MASK as usize), ..default() })],
+            ..default()
+        };
+
+        assert!(matches!(replay.to_bytes(), Err(ReplayError::RewardChoice(_))));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut bytes = Replay::default().to_bytes().unwrap();
+        bytes[4..6].copy_from_slice(&(REPLAY_VERSION - 1).to_le_bytes());
+
+        assert!(matches!(Replay::from_bytes(&bytes), Err(ReplayError::Invalid(_))));
+    }
+}
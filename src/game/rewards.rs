@@ -0,0 +1,140 @@
+use bevy::prelude::*;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::AppState;
+use super::{characters, player, summons, waves, input::PlayerInput, rng::GameRng, Difficulty, GameState, WaveState, Arena};
+
+pub const REWARD_CHOICES: usize = 2; // Number of rewards offered after every wave
+pub const REWARD_COLLECTIBLES: i32 = 8; // Ammount of collectibles given by a collectible reward
+pub const REWARD_MAX_HEALTH: i32 = 1; // Max health added by a health reward
+pub const REWARD_FIRING_RATE_MULTIPLIER: f32 = 0.85; // Summon firing rates are multiplied by this, so they shoot faster
+
+// Rewards last for the rest of the run
+// Scripted waves can choose a reward, see game::waves
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub enum Reward {
+    Collectibles {
+        collectible_type: usize,
+        ammount: i32,
+    },
+    MaxHealth,
+    FasterSummons,
+    FreeSummon {
+        summon_type: usize,
+    },
+}
+
+// Rewards the player can choose from inbetween waves, chosen when the wave ends
+#[derive(Resource, Default, Debug)]
+pub struct RewardOffer(pub Vec<Reward>);
+
+// Ui card for the reward at this index in the reward offer
+#[derive(Component)]
+pub struct RewardCard(pub usize);
+
+pub struct RewardPlugin;
+
+impl Plugin for RewardPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<RewardOffer>()
+            .add_systems(OnExit(WaveState::Fight), offer_rewards.run_if(in_state(AppState::Game)))
+            .add_systems(Update, choose_reward.run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Reward)).run_if(in_state(GameState::Running)));
+    }
+}
+
+// Picks different rewards for the player to choose from, the reward of a scripted wave is always offered first
+pub fn offer_rewards(
+    mut reward_offer: ResMut<RewardOffer>,
+    difficulty: Res<Difficulty>,
+    wave_script: Res<waves::WaveScript>,
+    summon_types: Res<characters::SummonTypes>,
+    collectible_types: Res<characters::CollectibleTypes>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let rng = &mut game_rng.0;
+
+    let mut rewards = vec![
+        Reward::Collectibles {
+            collectible_type: rng.gen_range(0..collectible_types.0.len()),
+            ammount: REWARD_COLLECTIBLES,
+        },
+        Reward::MaxHealth,
+    ];
+
+    if summon_types.0.iter().any(|summon_info| summon_info.firing_rate.is_some()) {
+        rewards.push(Reward::FasterSummons);
+    }
+
+    // Sentries are placed by the player, so they aren't given away
+    let free_summons: Vec<usize> = summon_types.0.iter()
+        .enumerate()
+        .filter(|(_, summon_info)| summon_info.summon_type != Some(characters::SummonType::Sentry))
+        .map(|(i, _)| i)
+        .collect();
+    if let Some(summon_type) = free_summons.choose(rng) {
+        rewards.push(Reward::FreeSummon { summon_type: *summon_type });
+    }
+
+    rewards.shuffle(rng);
+    if let Some(reward) = wave_script.wave(difficulty.wave).and_then(|scripted_wave| scripted_wave.reward) {
+        rewards.retain(|offered| *offered != reward);
+        rewards.insert(0, reward);
+    }
+    rewards.truncate(REWARD_CHOICES);
+    reward_offer.0 = rewards;
+}
+
+// Applies the reward the player picked and starts the countdown to the next wave
+#[allow(clippy::too_many_arguments)]
+fn choose_reward(
+    mut commands: Commands,
+    player_input: Res<PlayerInput>,
+    reward_offer: Res<RewardOffer>,
+    mut summon_types: ResMut<characters::SummonTypes>,
+    mut player_query: Query<&mut player::Player>,
+    mut summon_query: Query<&mut characters::Character, With<summons::Summon>>,
+    arena: Res<Arena>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_wave_state: ResMut<NextState<WaveState>>,
+) {
+    let Some(reward) = player_input.reward_choice.and_then(|choice| reward_offer.0.get(choice)) else {
+        return;
+    };
+
+    match *reward {
+        Reward::Collectibles { collectible_type, ammount } => {
+            if let Ok(mut player) = player_query.get_single_mut() {
+                player.collectibles[collectible_type] += ammount;
+            }
+        },
+        Reward::MaxHealth => {
+            if let Ok(mut player) = player_query.get_single_mut() {
+                player.max_health += REWARD_MAX_HEALTH;
+            }
+        },
+        Reward::FasterSummons => {
+            for summon_info in summon_types.0.iter_mut() {
+                summon_info.firing_rate = summon_info.firing_rate.map(|firing_rate| firing_rate * REWARD_FIRING_RATE_MULTIPLIER);
+            }
+
+            // Summons that have already been spawned shoot faster too
+            for mut summon in summon_query.iter_mut() {
+                if let Some(shot_timer) = summon.shot_timer.as_mut() {
+                    shot_timer.set_duration(shot_timer.duration().mul_f32(REWARD_FIRING_RATE_MULTIPLIER));
+                }
+            }
+        },
+        Reward::FreeSummon { summon_type } => {
+            let rng = &mut game_rng.0;
+            let position = Vec3::new(rng.gen_range(0.0..arena.width), rng.gen_range(0.0..arena.height), 0.0);
+            summons::spawn_summon(&mut commands, summon_type, &summon_types.0[summon_type], position, &asset_server, &mut texture_atlas_layouts, rng);
+        },
+    }
+
+    next_wave_state.set(WaveState::Start);
+}
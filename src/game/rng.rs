@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::AppState;
+use super::GameSetupSystem;
+
+// Every random roll in gameplay comes from GameRng, so a run can be reproduced from it's seed
+
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+// Seed to use for every run, None picks a new random seed for each run
+#[derive(Resource, Default)]
+pub struct SeedSettings {
+    pub seed: Option<u64>,
+}
+
+// Seed of the current run
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct GameSeed(pub u64);
+
+pub struct RngPlugin;
+
+impl Plugin for RngPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SeedSettings>()
+            .add_systems(OnEnter(AppState::GameSetup), seed_game_rng.in_set(GameSetupSystem));
+    }
+}
+
+// Seeds the rng at the start of every run
+fn seed_game_rng(mut commands: Commands, seed_settings: Res<SeedSettings>) {
+    let seed = seed_settings.seed.unwrap_or_else(|| rand::thread_rng().gen());
+
+    commands.insert_resource(GameSeed(seed));
+    commands.insert_resource(GameRng(StdRng::seed_from_u64(seed)));
+}
@@ -0,0 +1,306 @@
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::fs;
+
+use crate::AppState;
+use super::{characters, helpers, player, summons, interpolation};
+use super::{high_scores::RunRecord, replay::ReplayState, rng::{GameRng, GameSeed}, score::Score};
+use super::{Difficulty, GameSetupSystem, GameState, WaveState};
+
+// A run is saved every time the countdown to the next wave starts, so it can be continued from the main menu
+// Nothing is happening between waves, so only the player, summons and run progress need to be saved
+
+pub const SAVE_VERSION: u32 = 1;
+pub const SAVE_FILE: &str = "save.ron";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RunSave {
+    pub version: u32,
+    pub seed: u64, // Seed the run was started with
+    pub rng_seed: u64, // The game rng is seeded with this at the start of the saved wave
+
+    pub wave: u32,
+    pub enemy_spawn_chance: Vec<u8>,
+    pub enemies: f32,
+
+    pub player: PlayerSave,
+    pub summons: Vec<SummonSave>,
+    pub summon_firing_rates: Vec<Option<f32>>, // Firing rate of every summon type, rewards can change these during a run
+
+    pub score: ScoreSave,
+    pub summons_used: Vec<u32>, // Summons spawned during the run, by summon type
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlayerSave {
+    pub health: i32,
+    pub max_health: i32,
+    pub collectibles: Vec<i32>,
+    pub sentries: Vec<usize>,
+    pub position: (f32, f32),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SummonSave {
+    pub type_index: usize,
+    pub health: i32,
+    pub position: (f32, f32),
+    pub sentry_ammo: Option<u32>,
+    pub sentry_lifetime_elapsed: Option<f32>, // Seconds the sentry has been placed for
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScoreSave {
+    pub kill_points: u64,
+    pub wave_points: u64,
+    pub collectible_points: u64,
+    pub time_points: u64,
+    pub best_streak: u32,
+}
+
+impl RunSave {
+    pub fn load(path: &Path) -> Result<RunSave, helpers::FileError> {
+        helpers::load_ron(path, SAVE_VERSION, |save: &RunSave| save.version)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), helpers::FileError> {
+        helpers::save_ron(self, path)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SaveSettings {
+    pub path: Option<PathBuf>, // Where the run is saved, the save is only kept in memory without a path
+}
+
+#[derive(Resource, Default)]
+pub struct SavedRun {
+    pub save: Option<RunSave>, // Last saved run, None if there is nothing to continue
+    current_run: bool, // True if the save belongs to the run being played
+}
+
+// Summon respawned from the save, it was already counted in the run record when the run was saved
+#[derive(Component)]
+pub struct Restored;
+
+// Inserted to continue the saved run the next time a game is setup
+#[derive(Resource)]
+pub struct PendingResume;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SaveSettings>()
+            .init_resource::<SavedRun>()
+            .add_systems(Startup, load_save)
+            .add_systems(OnEnter(AppState::GameSetup), resume_run.after(GameSetupSystem))
+            .add_systems(OnEnter(WaveState::Start), save_run.after(player::heal_player).run_if(in_state(AppState::Game)))
+            .add_systems(OnEnter(GameState::GameOver), delete_save);
+    }
+}
+
+// Default location of the save, in the user data directory of the platform
+pub fn default_path() -> Option<PathBuf> {
+    helpers::data_directory().map(|directory| directory.join(SAVE_FILE))
+}
+
+fn load_save(settings: Res<SaveSettings>, mut saved_run: ResMut<SavedRun>) {
+    // A save that can't be read can't be continued
+    if let Some(save) = settings.path.as_deref().and_then(|path| helpers::load_or_move_bad(path, "save", RunSave::load)) {
+        saved_run.save = Some(save);
+    }
+}
+
+// Saves the run before the countdown to the next wave
+// The game rng is reseeded here even when the run isn't saved, so a continued run rolls the same numbers as the original
+#[allow(clippy::too_many_arguments)]
+fn save_run(
+    settings: Res<SaveSettings>,
+    mut saved_run: ResMut<SavedRun>,
+    mut game_rng: ResMut<GameRng>,
+    game_seed: Res<GameSeed>,
+    difficulty: Res<Difficulty>,
+    score: Res<Score>,
+    run_record: Res<RunRecord>,
+    summon_types: Res<characters::SummonTypes>,
+    replay_state: Res<ReplayState>,
+    player_query: Query<(&player::Player, &interpolation::Interpolated)>,
+    summon_query: Query<(&characters::Character, &interpolation::Interpolated, Option<&summons::Sentry>), With<summons::Summon>>,
+) {
+    let rng_seed = game_rng.0.gen();
+    game_rng.0 = StdRng::seed_from_u64(rng_seed);
+
+    // Replays are played from their recording
+    if matches!(*replay_state, ReplayState::Playing(_)) {
+        return;
+    }
+
+    let Ok((player, player_interpolated)) = player_query.get_single() else {
+        return;
+    };
+
+    let summons = summon_query.iter()
+        .filter(|(summon, _, _)| summon.health > 0)
+        .map(|(summon, interpolated, sentry)| SummonSave {
+            type_index: summon.type_index,
+            health: summon.health,
+            position: (interpolated.translation().x, interpolated.translation().y),
+            sentry_ammo: sentry.and_then(|sentry| sentry.ammo),
+            sentry_lifetime_elapsed: sentry.and_then(|sentry| sentry.lifetime_timer.as_ref()).map(|lifetime_timer| lifetime_timer.elapsed_secs()),
+        })
+        .collect();
+
+    let save = RunSave {
+        version: SAVE_VERSION,
+        seed: game_seed.0,
+        rng_seed,
+
+        wave: difficulty.wave,
+        enemy_spawn_chance: difficulty.enemy_spawn_chance.clone(),
+        enemies: difficulty.enemies,
+
+        player: PlayerSave {
+            health: player.health,
+            max_health: player.max_health,
+            collectibles: player.collectibles.clone(),
+            sentries: player.sentries.clone(),
+            position: (player_interpolated.translation().x, player_interpolated.translation().y),
+        },
+        summons,
+        summon_firing_rates: summon_types.0.iter().map(|summon_info| summon_info.firing_rate).collect(),
+
+        score: ScoreSave {
+            kill_points: score.kill_points,
+            wave_points: score.wave_points,
+            collectible_points: score.collectible_points,
+            time_points: score.time_points,
+            best_streak: score.best_streak,
+        },
+        summons_used: run_record.summons.clone(),
+    };
+
+    if let Some(path) = &settings.path {
+        if let Err(error) = save.save(path) {
+            error!("Could not save run {}: {}", path.display(), error);
+        }
+    }
+
+    saved_run.save = Some(save);
+    saved_run.current_run = true;
+}
+
+// Replaces the new run that was just setup with the saved one
+#[allow(clippy::too_many_arguments)]
+fn resume_run(
+    mut commands: Commands,
+    pending_resume: Option<Res<PendingResume>>,
+    mut saved_run: ResMut<SavedRun>,
+    mut replay_state: ResMut<ReplayState>,
+    mut summon_types: ResMut<characters::SummonTypes>,
+    mut score: ResMut<Score>,
+    mut run_record: ResMut<RunRecord>,
+    mut game_rng: ResMut<GameRng>,
+    mut player_query: Query<(&mut player::Player, &mut Transform, &mut interpolation::Interpolated)>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    saved_run.current_run = false;
+    if pending_resume.is_none() {
+        return;
+    }
+    commands.remove_resource::<PendingResume>();
+
+    let Some(save) = saved_run.save.clone() else {
+        return;
+    };
+
+    // Summon types are checked against the content file, which may have changed since the run was saved
+    if save.summon_firing_rates.len() != summon_types.0.len() || save.summons.iter().any(|summon_save| summon_save.type_index >= summon_types.0.len()) {
+        error!("Could not continue the saved run, it doesn't match the game content");
+        return;
+    }
+
+    // A recording starts from the beginning of a run, so a continued run can't be recorded
+    if let ReplayState::Recording { .. } = *replay_state {
+        warn!("Continued runs are not recorded");
+        *replay_state = ReplayState::Idle;
+    }
+
+    commands.insert_resource(GameSeed(save.seed));
+    commands.insert_resource(Difficulty {
+        wave: save.wave,
+        enemy_spawn_chance: save.enemy_spawn_chance.clone(),
+        enemies: save.enemies,
+    });
+
+    for (summon_info, firing_rate) in summon_types.0.iter_mut().zip(save.summon_firing_rates.iter()) {
+        summon_info.firing_rate = *firing_rate;
+    }
+
+    score.kill_points = save.score.kill_points;
+    score.wave_points = save.score.wave_points;
+    score.collectible_points = save.score.collectible_points;
+    score.time_points = save.score.time_points;
+    score.best_streak = save.score.best_streak;
+    run_record.summons = save.summons_used.clone();
+
+    if let Ok((mut player, mut transform, mut interpolated)) = player_query.get_single_mut() {
+        player.health = save.player.health;
+        player.max_health = save.player.max_health;
+        player.collectibles = save.player.collectibles.clone();
+        player.sentries = save.player.sentries.clone();
+
+        let position = Vec3::new(save.player.position.0, save.player.position.1, 0.0);
+        transform.translation = position;
+        *interpolated = interpolation::Interpolated::new(position);
+    }
+
+    for summon_save in save.summons.iter() {
+        let position = Vec3::new(summon_save.position.0, summon_save.position.1, 0.0);
+        let summon_entity = summons::spawn_summon(&mut commands, summon_save.type_index, &summon_types.0[summon_save.type_index], position, &asset_server, &mut texture_atlas_layouts, &mut game_rng.0);
+        commands.entity(summon_entity).insert(Restored);
+
+        let sentry_ammo = summon_save.sentry_ammo;
+        let sentry_lifetime_elapsed = summon_save.sentry_lifetime_elapsed;
+        let health = summon_save.health;
+        commands.add(move |world: &mut World| {
+            let mut summon_entity = world.entity_mut(summon_entity);
+            if let Some(mut summon) = summon_entity.get_mut::<characters::Character>() {
+                summon.health = health;
+            }
+            if let Some(mut sentry) = summon_entity.get_mut::<summons::Sentry>() {
+                sentry.ammo = sentry_ammo;
+                if let (Some(lifetime_timer), Some(elapsed)) = (sentry.lifetime_timer.as_mut(), sentry_lifetime_elapsed) {
+                    lifetime_timer.set_elapsed(Duration::from_secs_f32(elapsed));
+                }
+            }
+        });
+    }
+
+    // Summon animations use the rng, reseed it after so the run continues exactly like it was saved
+    game_rng.0 = StdRng::seed_from_u64(save.rng_seed);
+    saved_run.current_run = true;
+}
+
+// A run that has ended can't be continued
+fn delete_save(settings: Res<SaveSettings>, mut saved_run: ResMut<SavedRun>) {
+    if !saved_run.current_run {
+        return;
+    }
+
+    saved_run.save = None;
+    saved_run.current_run = false;
+    if let Some(path) = &settings.path {
+        if path.exists() {
+            if let Err(error) = fs::remove_file(path) {
+                error!("Could not delete save {}: {}", path.display(), error);
+            }
+        }
+    }
+}
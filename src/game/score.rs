@@ -0,0 +1,107 @@
+use bevy::prelude::*;
+use crate::AppState;
+use super::{characters, GameState, WaveState, ScoreIncrease, Difficulty};
+
+pub const WAVE_POINTS: u64 = 100; // Points for clearing a wave, multiplied by the wave number
+pub const COLLECTIBLE_POINTS: u64 = 2; // Points for picking up a collectible
+pub const TIME_POINTS: u64 = 1; // Points for every second survived while fighting
+
+pub const STREAK_TIME: f32 = 2.0; // Kills within this many seconds of each other continue a kill streak
+pub const STREAK_KILLS_PER_MULTIPLIER: u32 = 5; // The kill multiplier goes up by one every time the streak reaches this many kills
+pub const MAX_STREAK_MULTIPLIER: u64 = 4;
+
+// Points from a run, inserted again every time a game is setup
+#[derive(Resource, Debug, Clone)]
+pub struct Score {
+    pub kill_points: u64,
+    pub wave_points: u64,
+    pub collectible_points: u64,
+    pub time_points: u64,
+
+    pub streak: u32, // Kills in the current kill streak
+    pub best_streak: u32,
+    streak_timer: Timer, // The streak ends when this finishes
+    survival_timer: Timer, // Gives time points every time it finishes
+}
+
+impl Default for Score {
+    fn default() -> Self {
+        let mut streak_timer = Timer::from_seconds(STREAK_TIME, TimerMode::Once);
+        streak_timer.tick(streak_timer.duration()); // No streak to continue at the start of a run
+
+        Score {
+            kill_points: 0,
+            wave_points: 0,
+            collectible_points: 0,
+            time_points: 0,
+            streak: 0,
+            best_streak: 0,
+            streak_timer,
+            survival_timer: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl Score {
+    pub fn total(&self) -> u64 {
+        self.kill_points + self.wave_points + self.collectible_points + self.time_points
+    }
+
+    // Kill points are multiplied by this
+    pub fn streak_multiplier(&self) -> u64 {
+        (1 + (self.streak / STREAK_KILLS_PER_MULTIPLIER) as u64).min(MAX_STREAK_MULTIPLIER)
+    }
+}
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Score>()
+            .add_systems(OnEnter(AppState::GameSetup), reset_score.in_set(super::GameSetupSystem))
+
+            // Score increases are sent during FixedUpdate, so they are all read in the same tick
+            .add_systems(FixedPostUpdate, increase_score.run_if(in_state(AppState::Game)))
+            .add_systems(FixedUpdate, time_survived.run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)));
+    }
+}
+
+fn reset_score(mut score: ResMut<Score>) {
+    *score = Score::default();
+}
+
+fn increase_score(
+    mut score: ResMut<Score>,
+    mut score_increase_events: EventReader<ScoreIncrease>,
+    enemy_types: Res<characters::EnemyTypes>,
+    difficulty: Res<Difficulty>,
+    time: Res<Time>,
+) {
+    // The timers tick without marking the score as changed, so it only changes when points or the streak do
+    score.bypass_change_detection().streak_timer.tick(time.delta());
+    if score.streak_timer.finished() && score.streak != 0 {
+        score.streak = 0;
+    }
+
+    for event in score_increase_events.read() {
+        match *event {
+            ScoreIncrease::EnemyKilled { enemy_type } => {
+                score.streak += 1;
+                score.best_streak = score.best_streak.max(score.streak);
+                score.streak_timer.reset();
+
+                score.kill_points += enemy_types.0[enemy_type].score as u64 * score.streak_multiplier();
+            },
+            ScoreIncrease::WaveCleared => score.wave_points += WAVE_POINTS * (difficulty.wave as u64 + 1),
+            ScoreIncrease::CollectiblePickedUp => score.collectible_points += COLLECTIBLE_POINTS,
+        }
+    }
+}
+
+fn time_survived(mut score: ResMut<Score>, time: Res<Time>) {
+    let times_finished = score.bypass_change_detection().survival_timer.tick(time.delta()).times_finished_this_tick();
+    if times_finished > 0 {
+        score.time_points += TIME_POINTS * times_finished as u64;
+    }
+}
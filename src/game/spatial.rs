@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+use crate::{AppState, art};
+use super::{GameState, player, enemies, summons, characters, projectiles, collectibles, hitboxes};
+
+pub const SPATIAL_CELL_SIZE: f32 = 64.0 * art::SPRITE_SCALE; // Width and height of a grid cell, roughly the size of the larger hitboxes
+
+// What an entry in the spatial grid is, so systems only look at the entities they care about
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpatialKind {
+    Player,
+    Enemy,
+    Summon,
+    EnemyProjectile,
+    SummonProjectile,
+    Collectible,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SpatialEntry {
+    pub entity: Entity,
+    pub kind: SpatialKind,
+    pub position: Vec3,
+    pub hitbox: art::Hitbox,
+}
+
+// Uniform grid of every entity that collides with something, rebuilt every tick
+// Entries are stored in the cell that contains their position
+// Queries are widened by the furthest reaching hitbox in the grid, so hitboxes that hang over a cell's edge are still found
+#[derive(Resource, Debug)]
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<SpatialEntry>>,
+    max_reach: Vec2,
+    occupied: Option<(IVec2, IVec2)>, // Smallest and largest cell with an entry, so searches know when to stop
+    len: usize,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        SpatialGrid::new(SPATIAL_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    pub fn new(cell_size: f32) -> Self {
+        SpatialGrid {
+            cell_size,
+            cells: HashMap::new(),
+            max_reach: Vec2::ZERO,
+            occupied: None,
+            len: 0,
+        }
+    }
+
+    // Empties the grid, cell vecs used by the last build are kept so rebuilding doesn't allocate every tick
+    // Cells the last build didn't use are dropped, so cells entities have moved out of don't pile up
+    pub fn clear(&mut self) {
+        self.cells.retain(|_, cell| !cell.is_empty());
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        self.max_reach = Vec2::ZERO;
+        self.occupied = None;
+        self.len = 0;
+    }
+
+    pub fn insert(&mut self, entry: SpatialEntry) {
+        let cell = self.cell(entry.position.truncate());
+
+        self.max_reach = self.max_reach.max(entry.hitbox.reach());
+        self.occupied = match self.occupied {
+            Some((min, max)) => Some((min.min(cell), max.max(cell))),
+            None => Some((cell, cell)),
+        };
+        self.cells.entry(cell).or_default().push(entry);
+        self.len += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    // Every entry whose position is within the given distance of the position on both axes
+    // Entries may be further away than that, this is only a broadphase
+    pub fn nearby(&self, position: &Vec3, distance: Vec2) -> impl Iterator<Item = &SpatialEntry> {
+        let min = self.cell(position.truncate() - distance);
+        let max = self.cell(position.truncate() + distance);
+
+        (min.x..=max.x)
+            .flat_map(move |x| (min.y..=max.y).map(move |y| IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+    }
+
+    // Every entry whose hitbox collides with the given hitbox
+    pub fn colliding<'a>(&'a self, hitbox: &'a art::Hitbox, position: &'a Vec3) -> impl Iterator<Item = &'a SpatialEntry> {
+        self.nearby(position, hitbox.reach() + self.max_reach)
+            .filter(|entry| hitboxes::are_hitboxes_colliding(hitbox, position, &entry.hitbox, &entry.position))
+    }
+
+    // Every entry whose position is within the radius of the position
+    pub fn within_radius<'a>(&'a self, position: &'a Vec3, radius: f32) -> impl Iterator<Item = &'a SpatialEntry> {
+        self.nearby(position, Vec2::splat(radius))
+            .filter(move |entry| entry.position.distance(*position) < radius)
+    }
+
+    // The closest entry of the given kind
+    // Searches outwards one ring of cells at a time, and stops once no closer entry can be in the next ring
+    pub fn nearest(&self, position: &Vec3, kind: SpatialKind) -> Option<&SpatialEntry> {
+        let (occupied_min, occupied_max) = self.occupied?;
+        let center = self.cell(position.truncate());
+        let last_ring = (occupied_min - center).abs().max((occupied_max - center).abs()).max_element();
+
+        let mut nearest: Option<(&SpatialEntry, f32)> = None;
+        for ring in 0..=last_ring {
+            for cell in ring_cells(center, ring) {
+                let Some(entries) = self.cells.get(&cell) else {
+                    continue;
+                };
+
+                for entry in entries.iter().filter(|entry| entry.kind == kind) {
+                    let distance = entry.position.distance(*position);
+                    if nearest.is_none_or(|(_, nearest_distance)| distance < nearest_distance) {
+                        nearest = Some((entry, distance));
+                    }
+                }
+            }
+
+            // Everything in the next ring is atleast this far away
+            if let Some((_, nearest_distance)) = nearest {
+                if nearest_distance <= ring as f32 * self.cell_size {
+                    break;
+                }
+            }
+        }
+
+        nearest.map(|(entry, _)| entry)
+    }
+}
+
+// Cells on the edge of the square that is (ring) cells away from the center
+fn ring_cells(center: IVec2, ring: i32) -> impl Iterator<Item = IVec2> {
+    let top_and_bottom = (-ring..=ring).flat_map(move |x| [IVec2::new(x, -ring), IVec2::new(x, ring)]);
+    let sides = (1 - ring..ring).flat_map(move |y| [IVec2::new(-ring, y), IVec2::new(ring, y)]);
+
+    top_and_bottom
+        .chain(sides)
+        .take(if ring == 0 { 1 } else { usize::MAX }) // The center would be returned twice
+        .map(move |offset| center + offset)
+}
+
+// Systems that move entities run before the grid is rebuilt, systems that check collisions run after
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpatialGridSystem;
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<SpatialGrid>()
+            .add_systems(OnEnter(AppState::GameSetup), clear_spatial_grid)
+            .add_systems(FixedUpdate, update_spatial_grid.in_set(SpatialGridSystem).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+    }
+}
+
+fn clear_spatial_grid(mut spatial_grid: ResMut<SpatialGrid>) {
+    spatial_grid.clear();
+}
+
+#[allow(clippy::too_many_arguments)]
+fn update_spatial_grid(
+    mut spatial_grid: ResMut<SpatialGrid>,
+    player_query: Query<(&Transform, Entity), With<player::Player>>,
+    enemy_query: Query<(&Transform, &characters::Character, &TextureAtlas, Entity), With<enemies::Enemy>>,
+    summon_query: Query<(&Transform, &characters::Character, &TextureAtlas, Entity), With<summons::Summon>>,
+    projectile_query: Query<(&Transform, &projectiles::Projectile, Entity)>,
+    collectible_query: Query<(&Transform, &collectibles::Collectible, Entity)>,
+    enemy_types: Res<characters::EnemyTypes>,
+    summon_types: Res<characters::SummonTypes>,
+    projectile_types: Res<projectiles::ProjectileTypes>,
+) {
+    spatial_grid.clear();
+
+    for (transform, entity) in player_query.iter() {
+        spatial_grid.insert(SpatialEntry {
+            entity,
+            kind: SpatialKind::Player,
+            position: transform.translation,
+            hitbox: art::PLAYER_HITBOX,
+        });
+    }
+
+    for (transform, enemy, texture_atlas, entity) in enemy_query.iter() {
+        spatial_grid.insert(SpatialEntry {
+            entity,
+            kind: SpatialKind::Enemy,
+            position: transform.translation,
+            hitbox: hitboxes::animation_hitbox(&enemy_types.0[enemy.type_index].animation_information, transform, texture_atlas.index),
+        });
+    }
+
+    for (transform, summon, texture_atlas, entity) in summon_query.iter() {
+        spatial_grid.insert(SpatialEntry {
+            entity,
+            kind: SpatialKind::Summon,
+            position: transform.translation,
+            hitbox: hitboxes::animation_hitbox(&summon_types.0[summon.type_index].animation_information, transform, texture_atlas.index),
+        });
+    }
+
+    for (transform, projectile, entity) in projectile_query.iter() {
+        let projectile_info = &projectile_types.0[projectile.projectile_types_index];
+        spatial_grid.insert(SpatialEntry {
+            entity,
+            kind: if projectile_info.enemy { SpatialKind::EnemyProjectile } else { SpatialKind::SummonProjectile },
+            position: transform.translation,
+            hitbox: projectile_info.sprite_information.hitbox,
+        });
+    }
+
+    for (transform, collectible, entity) in collectible_query.iter() {
+        spatial_grid.insert(SpatialEntry {
+            entity,
+            kind: SpatialKind::Collectible,
+            position: transform.translation,
+            hitbox: collectible.hitbox,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(position: Vec3) -> SpatialEntry {
+        SpatialEntry {
+            entity: Entity::PLACEHOLDER,
+            kind: SpatialKind::Enemy,
+            position,
+            hitbox: art::Hitbox::Circle { radius: 1.0, offset: Vec2::ZERO },
+        }
+    }
+
+    #[test]
+    fn len_counts_entries() {
+        let mut grid = SpatialGrid::new(10.0);
+        assert!(grid.is_empty());
+
+        grid.insert(entry(Vec3::ZERO));
+        grid.insert(entry(Vec3::new(1.0, 1.0, 0.0)));
+        grid.insert(entry(Vec3::new(100.0, -50.0, 0.0)));
+        assert_eq!(grid.len(), 3);
+
+        grid.clear();
+        assert_eq!(grid.len(), 0);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn clear_drops_unused_cells() {
+        let mut grid = SpatialGrid::new(10.0);
+
+        // An entry moving across the grid one cell per rebuild
+        for x in 0..100 {
+            grid.clear();
+            grid.insert(entry(Vec3::new(x as f32 * 10.0, 0.0, 0.0)));
+        }
+        assert!(grid.cells.len() <= 2);
+        assert_eq!(grid.len(), 1);
+    }
+}
@@ -1,161 +1,294 @@
 use bevy::prelude::*;
 use rand::Rng;
-use bevy::window::PrimaryWindow;
-use std::time::{Instant, Duration};
 
 use crate::{game, AppState, art};
-use game::{WaveState, GameState, characters, helpers, enemies, projectiles, player, NANOS_PER_MILLIS, animation};
+use game::{WaveState, GameState, Arena, characters, helpers, projectiles, player, rng::GameRng, input::PlayerInput, interpolation, spatial, animation};
 
 #[derive(Component)]
 pub struct Summon;
 
-pub const SUMMON_RADIUS: f32 = 16.0 * art::SPRITE_SCALE; // Radius which summons want to keep clear of other summons
+// Summons that are placed by the player and can't move
+#[derive(Component)]
+pub struct Sentry {
+    target: Option<Entity>, // Enemy the sentry is aiming at
+    aim_timer: Timer, // The sentry can shoot once it has finished aiming at it's target
+    pub lifetime_timer: Option<Timer>,
+    pub ammo: Option<u32>, // Shots left
+}
+
+// Ui slot in the summon bar for the summon type at this index
+#[derive(Component)]
+pub struct SummonSlot(pub usize);
 
-#[derive(Resource, Clone)]
-pub struct SummonPositions(pub Vec<Option<Vec3>>);
+// Spend collectibles on summons as soon as the player has enough, instead of waiting for the player to buy them
+// Sentries are still placed by the player
+#[derive(Resource, Default, Debug)]
+pub struct AutoSpend(pub bool);
+
+pub const SUMMON_RADIUS: f32 = 16.0 * art::SPRITE_SCALE; // Radius which summons want to keep clear of other summons
 
 pub struct SummonPlugin;
 
 impl Plugin for SummonPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(OnEnter(AppState::GameSetup), insert_summon_positions)
-            .add_systems(Update, (move_summons, spawn_summons).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)))
-            .add_systems(Update, shoot_ranged_attacks.run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)));
+            .init_resource::<AutoSpend>()
+            .add_systems(FixedUpdate, (move_summons.before(spatial::SpatialGridSystem), spawn_summons.run_if(auto_spend_enabled)).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)))
+            .add_systems(Update, place_sentries.run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)))
+            .add_systems(Update, buy_summons.run_if(not(auto_spend_enabled)).run_if(in_state(AppState::Game)).run_if(not(in_state(WaveState::Reward))).run_if(in_state(GameState::Running)))
+            .add_systems(FixedUpdate, (shoot_ranged_attacks.after(spatial::SpatialGridSystem), expire_sentries).run_if(in_state(AppState::Game)).run_if(in_state(WaveState::Fight)).run_if(in_state(GameState::Running)));
     }
 }
 
-fn insert_summon_positions(mut commands: Commands) {
-    commands.insert_resource(SummonPositions(Vec::new()));
+
+fn auto_spend_enabled(auto_spend: Res<AutoSpend>) -> bool {
+    auto_spend.0
 }
 
+// Buys the summon the player picked from the summon bar
+// Summons bought with a hotkey are placed at the cursor, summons bought by clicking the bar are placed next to the player
+// Sentries can't move, so a sentry bought by clicking the bar or without a cursor waits to be placed at the cursor like an auto spend sentry
+fn buy_summons(
+    mut commands: Commands,
+    player_input: Res<PlayerInput>,
+    summon_types: Res<characters::SummonTypes>,
+    mut player_query: Query<(&mut player::Player, &Transform)>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    let Some(type_index) = player_input.buy_summon else {
+        return;
+    };
+    let Some(summon_info) = summon_types.0.get(type_index) else {
+        return;
+    };
+
+    if let Ok((mut player, player_transform)) = player_query.get_single_mut() {
+        if !summon_info.affordable(&player.collectibles) {
+            return;
+        }
+
+        let cursor_position = player_input.cursor_position.filter(|_| !player_input.buy_summon_clicked);
+        let position = match cursor_position {
+            Some(cursor_position) => cursor_position,
+            None if summon_info.summon_type == Some(characters::SummonType::Sentry) => {
+                spend_collectibles(&mut player, summon_info);
+                player.sentries.push(type_index);
+                return;
+            },
+            None => player_transform.translation,
+        };
+
+        spend_collectibles(&mut player, summon_info);
+        spawn_summon(&mut commands, type_index, summon_info, position, &asset_server, &mut texture_atlas_layouts, &mut game_rng.0);
+    }
+}
 
 // Spawn summons based of player collectibles
+#[allow(clippy::too_many_arguments)]
 fn spawn_summons(
     mut commands: Commands,
-    window_query: Query<&Window, With<PrimaryWindow>>,
+    arena: Res<Arena>,
+    player_input: Res<PlayerInput>,
     summon_types: Res<characters::SummonTypes>,
-    mut summon_positions: ResMut<SummonPositions>,
     mut player_query: Query<&mut player::Player>,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut game_rng: ResMut<GameRng>,
 ) {
-    let window = window_query.get_single().unwrap();
-    let cursor_position = window.cursor_position();
-    let mut rng = rand::thread_rng();
+    let rng = &mut game_rng.0;
 
     if let Some(mut player) = player_query.iter_mut().next() {
+
+        // Sentries are made first, otherwise the other summons would spend the collectibles they need
+        for (i, summon_info) in summon_types.0.iter().enumerate() {
+            if summon_info.summon_type == Some(characters::SummonType::Sentry) && summon_info.affordable(&player.collectibles) {
+                spend_collectibles(&mut player, summon_info);
+                player.sentries.push(i);
+            }
+        }
         
         // Iterate through collectibles
-        for (i, collectibles_amount) in player.collectibles.iter_mut().enumerate() {
+        for i in 0..player.collectibles.len() {
 
             // Check summons against this type of collectible to see if they can be spawned
             for (j, summon_info) in summon_types.0.iter().enumerate() {
-                if let Some(ammount_required) = summon_info.collectible_ammount_required {
-
-                    // Check the player has enough of the correct type of collectibles to spawn the summon
-                    if summon_info.collectible_type_required.unwrap() == i && *collectibles_amount >= ammount_required {
-                        *collectibles_amount -= ammount_required;
-
-                        // Spawn summon at the cursor position, if the cursor is off screen spawn at a random position
-                        let position = match cursor_position {
-                          Some(pos) => Vec3::new(pos.x, (window.height() - pos.y).abs(), 0.0),
-                          None => Vec3::new(rng.gen_range(0.0..window.width()), rng.gen_range(0.0..window.height()), 0.0),
-                        };
-
-                        let summon = characters::Character {
-                            last_shot: Some(Instant::now()),
-                            summon: true,
-                            last_damage: Instant::now(),
-                            health: summon_info.max_health,
-                            direction_vector: Vec3::ZERO,
-                            type_index: j,
-                            position_index: summon_positions.0.len(),
-                            last_animation_frame: Instant::now() + Duration::new(0, rng.gen_range(animation::ANIMATION_START_OFFSET[0] * NANOS_PER_MILLIS..animation::ANIMATION_START_OFFSET[1] * NANOS_PER_MILLIS)),
-                        };
-
-                        summon_positions.0.push(Some(position));
-
-                        let animation_information = summon_info.animation_information;
-                        let texture: Handle<Image> = asset_server.load(animation_information.spritesheet_path);
-
-                        let layout = TextureAtlasLayout::from_grid(
-                            animation_information.sprite_size,
-                            animation_information.frames,
-                            1,
-                            None,
-                            None,
-                        );
-                        let layout = texture_atlas_layouts.add(layout);
-
-                        commands.spawn(
-                            (
-                                summon,
-                                Summon,
-                                animation::AnimationSprite {
-                                    frames: animation_information.frames,
-                                    facing_right: animation_information.sprite_faces_right,
-                                },
-                                game::GameComponent,
-                                SpriteSheetBundle {
-                                    texture: texture,
-                                    atlas: TextureAtlas {
-                                        layout: layout,
-                                        index: 0,
-                                    },
-                                    transform: Transform {
-                                        translation: position,
-                                        scale: Vec3::splat(art::SPRITE_SCALE),
-                                        ..default()
-                                    },
-                                    visibility: Visibility::Visible,
-                                    ..default()
-                                },
-                            )
-                        );
-                    }
+                if summon_info.summon_type == Some(characters::SummonType::Sentry) || summon_info.collectible_type_required != Some(i) {
+                    continue;
+                }
+
+                // Check the player has enough of the correct type of collectibles to spawn the summon
+                if summon_info.affordable(&player.collectibles) {
+                    spend_collectibles(&mut player, summon_info);
+
+                    // Spawn summon at the cursor position, if the cursor is off screen spawn at a random position
+                    let position = match player_input.cursor_position {
+                      Some(pos) => pos,
+                      None => Vec3::new(rng.gen_range(0.0..arena.width), rng.gen_range(0.0..arena.height), 0.0),
+                    };
+
+                    spawn_summon(&mut commands, j, summon_info, position, &asset_server, &mut texture_atlas_layouts, rng);
                 }
             }
         }
     }
 }
 
-fn move_summons(
-    window_query: Query<&Window, With<PrimaryWindow>>,
-    mut summon_query: Query<(&mut Transform, &mut characters::Character), With<Summon>>,
+fn spend_collectibles(player: &mut player::Player, summon_info: &characters::CharacterInfo) {
+    for (collectible_type, ammount) in summon_info.cost() {
+        player.collectibles[collectible_type] -= ammount;
+    }
+}
+
+// Places the oldest sentry the player has made at the cursor
+fn place_sentries(
+    mut commands: Commands,
+    player_input: Res<PlayerInput>,
     summon_types: Res<characters::SummonTypes>,
-    mut summon_positions: ResMut<SummonPositions>,
-    buttons: Res<ButtonInput<MouseButton>>,
+    mut player_query: Query<&mut player::Player>,
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    mut game_rng: ResMut<GameRng>,
+) {
+    if !player_input.place_sentry {
+        return;
+    }
+
+    // Sentries can't be moved after they are placed, so they are only placed where the player is pointing
+    let Some(position) = player_input.cursor_position else {
+        return;
+    };
+
+    if let Some(mut player) = player_query.iter_mut().next() {
+        if player.sentries.is_empty() {
+            return;
+        }
+
+        let type_index = player.sentries.remove(0);
+        spawn_summon(&mut commands, type_index, &summon_types.0[type_index], position, &asset_server, &mut texture_atlas_layouts, &mut game_rng.0);
+    }
+}
+
+pub fn spawn_summon(
+    commands: &mut Commands,
+    type_index: usize,
+    summon_info: &characters::CharacterInfo,
+    position: Vec3,
+    asset_server: &AssetServer,
+    texture_atlas_layouts: &mut Assets<TextureAtlasLayout>,
+    rng: &mut impl Rng,
+) -> Entity {
+    let summon = characters::Character {
+        shot_timer: summon_info.firing_rate.map(|firing_rate| Timer::from_seconds(firing_rate, TimerMode::Repeating)),
+        summon: true,
+        invulnarability_timer: Timer::from_seconds(summon_info.invulnarability_time, TimerMode::Once),
+        health: summon_info.max_health,
+        direction_vector: Vec3::ZERO,
+        type_index,
+        animation_timer: animation::animation_timer(rng),
+    };
+
+    let animation_information = &summon_info.animation_information;
+    let texture: Handle<Image> = asset_server.load(animation_information.spritesheet_path.to_string());
+
+    let layout = TextureAtlasLayout::from_grid(
+        animation_information.sprite_size,
+        animation_information.frames,
+        1,
+        None,
+        None,
+    );
+    let layout = texture_atlas_layouts.add(layout);
+
+    let mut summon_entity = commands.spawn(
+        (
+            summon,
+            Summon,
+            animation::AnimationSprite {
+                frames: animation_information.frames,
+                facing_right: animation_information.sprite_faces_right,
+            },
+            game::GameComponent,
+            interpolation::Interpolated::new(position),
+            SpriteSheetBundle {
+                texture,
+                atlas: TextureAtlas {
+                    layout,
+                    index: 0,
+                },
+                transform: Transform {
+                    translation: position,
+                    scale: Vec3::splat(art::SPRITE_SCALE),
+                    ..default()
+                },
+                visibility: Visibility::Visible,
+                ..default()
+            },
+        )
+    );
+
+    if let Some(sentry_info) = &summon_info.sentry {
+        summon_entity.insert(Sentry {
+            target: None,
+            aim_timer: Timer::from_seconds(sentry_info.aim_time, TimerMode::Once),
+            lifetime_timer: sentry_info.lifetime.map(|lifetime| Timer::from_seconds(lifetime, TimerMode::Once)),
+            ammo: sentry_info.ammo,
+        });
+    }
+
+    summon_entity.id()
+}
+
+// Sentries run out once they have fired all their ammo or their lifetime is over
+fn expire_sentries(
+    mut sentry_query: Query<(&mut characters::Character, &mut Sentry)>,
     time: Res<Time>,
 ) {
-    let window = window_query.get_single().unwrap();
-    let cursor_position = window.cursor_position();
+    for (mut summon, mut sentry) in sentry_query.iter_mut() {
+        let out_of_ammo = sentry.ammo == Some(0);
+        let expired = match sentry.lifetime_timer.as_mut() {
+            Some(lifetime_timer) => lifetime_timer.tick(time.delta()).finished(),
+            None => false,
+        };
 
-    for (mut transform, mut summon) in summon_query.iter_mut() {
-        let character_info = summon_types.0[summon.type_index];
+        if out_of_ammo || expired {
+            summon.health = 0;
+        }
+    }
+}
+
+fn move_summons(
+    player_input: Res<PlayerInput>,
+    mut summon_query: Query<(&mut Transform, &mut characters::Character, Entity), With<Summon>>,
+    summon_types: Res<characters::SummonTypes>,
+    spatial_grid: Res<spatial::SpatialGrid>,
+    time: Res<Time>,
+) {
+    for (mut transform, mut summon, entity) in summon_query.iter_mut() {
+        let character_info = &summon_types.0[summon.type_index];
 
         
         match character_info.summon_type.unwrap() {
             characters::SummonType::Sentry => continue, // Sentries cannot move
             characters::SummonType::Ranged => {
-                if !buttons.pressed(MouseButton::Left) {
+                if !player_input.move_ranged_summons {
                     continue // Don't move ranged summons if their hotkey isn't being pressed
                 }
             },
             characters::SummonType::Melee => {
-                if !buttons.pressed(MouseButton::Right) {
+                if !player_input.move_melee_summons {
                     continue // Don't move melee summons if their hotkey isn't being pressed
                 }
             },
         }
 
         // Avoid other summons
-        helpers::avoid_positions(&mut transform, &summon_positions.0, summon.position_index, SUMMON_RADIUS, &time);
+        helpers::avoid_positions(&mut transform, &spatial_grid, entity, spatial::SpatialKind::Summon, SUMMON_RADIUS, &time);
 
         // Get the direction vector for the summon
-        let direction_vector = match cursor_position {
+        let direction_vector = match player_input.cursor_position {
             Some(cursor_position) => {
-                let cursor_position = Vec3::new(cursor_position.x, (window.height() - cursor_position.y).abs(), 0.0);
                 let difference = helpers::vector_subtract(&cursor_position, &transform.translation);
 
                 if difference.length() > 32.0 {
@@ -171,76 +304,82 @@ fn move_summons(
         transform.translation += direction_vector * character_info.speed * time.delta_seconds();
 
         summon.direction_vector = direction_vector;
-
-         // Update enemy position
-        if summon_positions.0[summon.position_index] != None {
-            summon_positions.0[summon.position_index] = Some(transform.translation);
-        }
     }
 }
 
 // Shoots ranged and sentry summons ranged attacks
 fn shoot_ranged_attacks(
     mut commands: Commands,
-    mut summon_query: Query<(&Transform, &mut characters::Character), With<Summon>>,
-    enemy_positions: Res<enemies::EnemyPositions>,
+    mut summon_query: Query<(&Transform, &mut characters::Character, Option<&mut Sentry>, Entity), With<Summon>>,
+    spatial_grid: Res<spatial::SpatialGrid>,
     summon_types: Res<characters::SummonTypes>,
     projectile_types: Res<projectiles::ProjectileTypes>,
     asset_server: Res<AssetServer>,
+    time: Res<Time>,
 ) {
-    for (transform, mut summon) in summon_query.iter_mut() {
-        let character_info = summon_types.0[summon.type_index];
+    for (transform, mut summon, mut sentry, entity) in summon_query.iter_mut() {
+        let character_info = &summon_types.0[summon.type_index];
 
         // Melee enemies don't have projectiles
         if character_info.summon_type.unwrap() == characters::SummonType::Melee {
             continue;
         }
 
-        // Check if the summon is ready to fire again
-        if summon.last_shot.unwrap().elapsed().as_secs_f32() > summon_types.0[summon.type_index].firing_rate.unwrap() {
-            summon.last_shot = Some(Instant::now());
-
-            // Find the closest enemy to shoot at
-            let mut minimum_distance: Option<f32> = None;
-            let mut direction_vector: Option<Vec3> = None;
-            for position in enemy_positions.0.iter() {
-                if let Some(position) = position {
-                    let difference = helpers::vector_subtract(&position, &transform.translation);
-                    let distance = difference.length();
-                    
-                    // Update minimum distance and direction vector
-                    match minimum_distance {
-                        Some(min_distance) => {
-                            if distance < min_distance {
-                                minimum_distance = Some(distance);
-                                direction_vector = Some(difference.normalize());
-                            }
-                        },
-                        None => {
-                            minimum_distance = Some(distance);
-                            direction_vector = Some(difference.normalize());
-                        },
-                    }
-                }
+        // Shoot at the closest enemy
+        let target = spatial_grid.nearest(&transform.translation, spatial::SpatialKind::Enemy);
+        let direction_vector = target.map(|entry| helpers::vector_subtract(&entry.position, &transform.translation).normalize());
+
+        // Sentries only shoot at enemies in range, and have to turn to face a new target before shooting
+        if let Some(sentry) = sentry.as_mut() {
+            let sentry_info = character_info.sentry.as_ref().unwrap();
+            let target = target.filter(|entry| entry.position.distance(transform.translation) <= sentry_info.range);
+
+            if target.map(|entry| entry.entity) != sentry.target {
+                sentry.target = target.map(|entry| entry.entity);
+                sentry.aim_timer.reset();
             }
+            sentry.aim_timer.tick(time.delta());
 
+            if let Some(direction_vector) = direction_vector.filter(|_| target.is_some()) {
+                summon.direction_vector = direction_vector;
+            }
+
+            // Sentries that aren't aiming at anything are ready to shoot as soon as they have aimed
+            if target.is_none() || !sentry.aim_timer.finished() {
+                let shot_timer = summon.shot_timer.as_mut().unwrap();
+                shot_timer.set_elapsed(shot_timer.duration());
+                continue;
+            }
+        }
+
+        // Check if the summon is ready to fire again
+        let shot_timer = summon.shot_timer.as_mut().unwrap();
+        shot_timer.tick(time.delta());
+        if shot_timer.just_finished() {
             if let Some(direction_vector) = direction_vector {
+                if let Some(sentry) = sentry.as_mut() {
+                    sentry.ammo = sentry.ammo.map(|ammo| ammo - 1);
+                }
+
                 let projectile_type_index = summon_types.0[summon.type_index].projectile_types_index.unwrap();
 
                 commands.spawn(
                     (
                         projectiles::Projectile {
-                            direction_vector: direction_vector,
+                            direction_vector,
                             projectile_types_index: projectile_type_index,
+                            distance: 0.0,
+                            shooter: entity,
                         },
                         game::GameComponent,
+                        interpolation::Interpolated::new(transform.translation),
                         SpriteBundle {
                             transform: Transform {
                                 translation: transform.translation,
                                 scale: Vec3::splat(art::SPRITE_SCALE),
                                 ..default()
                             },
-                            texture: asset_server.load(projectile_types.0[projectile_type_index].sprite_information.sprite_path),
+                            texture: asset_server.load(projectile_types.0[projectile_type_index].sprite_information.sprite_path.to_string()),
                             ..default()
                         }
                     )
@@ -249,4 +388,3 @@ fn shoot_ranged_attacks(
         }
     }
 }
-
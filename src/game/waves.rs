@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use super::rewards;
+
+// Waves can be scripted in an optional file next to the content file, see art::WAVES_PATH
+// Scripted waves are played in order from the first wave, the procedural difficulty curve takes over after the last one
+// The script is loaded and validated with the game content, see game::content
+
+#[derive(Resource, Default, Debug)]
+pub struct WaveScript(pub Vec<ScriptedWave>);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ScriptedWave {
+    pub groups: Vec<SpawnGroup>,
+
+    #[serde(default)]
+    pub modifier: WaveModifier,
+    #[serde(default)]
+    pub reward: Option<rewards::Reward>, // Always one of the rewards offered after the wave
+}
+
+// Enemies of one type that spawn together
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct SpawnGroup {
+    pub enemy_type: usize,
+    pub count: u32,
+
+    #[serde(default)]
+    pub side: SpawnSide,
+    #[serde(default)]
+    pub delay: f32, // Seconds after the wave starts that the group spawns
+}
+
+// Side of the screen enemies spawn outside of
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum SpawnSide {
+    #[default]
+    Any,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+// Changes the stats of every enemy spawned in the wave
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct WaveModifier {
+    pub health: f32, // Enemy max health is multiplied by this
+    pub firing_rate: f32, // Enemy firing rates are multiplied by this, so less than 1 shoots faster
+}
+
+impl Default for WaveModifier {
+    fn default() -> Self {
+        WaveModifier {
+            health: 1.0,
+            firing_rate: 1.0,
+        }
+    }
+}
+
+impl WaveScript {
+
+    // The scripted wave with this number, None once the script has run out
+    pub fn wave(&self, wave: u32) -> Option<&ScriptedWave> {
+        self.0.get(wave as usize)
+    }
+}
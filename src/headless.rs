@@ -0,0 +1,237 @@
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::input::{ButtonState, InputPlugin};
+use bevy::input::keyboard::{Key, KeyboardInput, NativeKey};
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::gamepad::{GamepadEvent, GamepadConnection, GamepadConnectionEvent, GamepadInfo, GamepadButtonChangedEvent, GamepadAxisChangedEvent};
+use std::time::Duration;
+
+use crate::{AppState, AppStatePlugin, game, ui};
+use game::{WaveState, GameState, Arena, Difficulty, player::Player, characters::Character};
+use game::replay::{Replay, ReplayMismatch, ReplaySettings, ReplayState, PendingReplay};
+
+// Runs the game without a window or GPU, used to test the wave loop
+// Time advances by a fixed step every update so runs don't depend on how fast the machine is
+
+pub const HEADLESS_ARENA_SIZE: Vec2 = Vec2::new(1920.0, 1080.0);
+pub const HEADLESS_TIMESTEP: f32 = 1.0 / 60.0; // Seconds the game clock advances every update
+
+const MAX_LOADING_UPDATES: u32 = 10000; // Updates to wait for the content to load before giving up
+
+pub struct HeadlessGame {
+    pub app: App,
+}
+
+impl HeadlessGame {
+
+    // Creates a headless game with a virtual arena of the given size, and waits for the content to load
+    pub fn new(arena_size: Vec2) -> Self {
+        let mut app = App::new();
+
+        app
+            .add_plugins((MinimalPlugins, AssetPlugin::default(), InputPlugin))
+
+            // Sprite assets are normally registered by the rendering plugins
+            // Gameplay still creates handles to them, they just never get drawn
+            .init_asset::<Image>()
+            .init_asset::<TextureAtlasLayout>()
+
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(HEADLESS_TIMESTEP)))
+            .insert_resource(Arena {
+                width: arena_size.x,
+                height: arena_size.y,
+                cursor_position: None,
+            })
+            .add_plugins((AppStatePlugin, game::GamePlugin))
+
+            // Menus need a window so they aren't added, but buttons spawned by tests can be selected with a gamepad
+            .add_plugins(ui::navigation::NavigationPlugin);
+
+        app.finish();
+        app.cleanup();
+
+        let mut headless_game = HeadlessGame { app };
+
+        let mut updates = 0;
+        while headless_game.app_state() == AppState::Loading {
+            assert!(updates < MAX_LOADING_UPDATES, "Game content did not load");
+
+            // Content loads on the asset server's threads, give them time to finish
+            std::thread::sleep(Duration::from_millis(1));
+            headless_game.update();
+            updates += 1;
+        }
+
+        headless_game
+    }
+
+    // Advances the game by one fixed step
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    pub fn update_for(&mut self, updates: u32) {
+        for _ in 0..updates {
+            self.update();
+        }
+    }
+
+    // Advances the game until roughly the given number of game seconds have passed
+    pub fn update_for_seconds(&mut self, seconds: f32) {
+        self.update_for((seconds / HEADLESS_TIMESTEP).ceil() as u32);
+    }
+
+    // Starts a new game and waits until AppState::Game is entered
+    pub fn start_game(&mut self) {
+        self.set_app_state(AppState::GameSetup);
+        while self.app_state() != AppState::Game {
+            self.update();
+        }
+    }
+
+    // Starts a new game with the given seed, summons are bought automatically if auto_spend is true
+    pub fn start_seeded(&mut self, seed: u64, auto_spend: bool) {
+        self.app.world.insert_resource(game::summons::AutoSpend(auto_spend));
+        self.set_seed(Some(seed));
+        self.start_game();
+    }
+
+    // Continues the saved run and waits until AppState::Game is entered
+    pub fn continue_game(&mut self) {
+        self.app.world.insert_resource(game::save::PendingResume);
+        self.start_game();
+    }
+
+    // Seed used for every game started after this, None picks a random seed
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.app.world.resource_mut::<game::rng::SeedSettings>().seed = seed;
+    }
+
+    // Records every game started after this, the recording is returned by take_recording once the game ends
+    pub fn set_recording(&mut self, record: bool) {
+        self.app.world.resource_mut::<ReplaySettings>().record = record;
+    }
+
+    // The last recorded run, if it has finished
+    pub fn take_recording(&mut self) -> Option<Replay> {
+        let mut replay_state = self.app.world.resource_mut::<ReplayState>();
+        if let ReplayState::Recorded(replay) = &*replay_state {
+            let replay = replay.clone();
+            *replay_state = ReplayState::Idle;
+            return Some(replay);
+        }
+        None
+    }
+
+    // Plays a replay from the main menu until it has finished
+    // Returns the first frame where the run differs from the recording
+    pub fn play_replay(&mut self, replay: Replay) -> Option<ReplayMismatch> {
+        self.app.world.insert_resource(PendingReplay(replay));
+        loop {
+            self.update();
+            if let ReplayState::Finished(mismatch) = self.app.world.resource::<ReplayState>() {
+                return *mismatch;
+            }
+        }
+    }
+
+    // State changes are applied on the next update
+    pub fn set_app_state(&mut self, state: AppState) {
+        self.app.world.resource_mut::<NextState<AppState>>().set(state);
+    }
+
+    pub fn set_wave_state(&mut self, state: WaveState) {
+        self.app.world.resource_mut::<NextState<WaveState>>().set(state);
+    }
+
+    pub fn set_game_state(&mut self, state: GameState) {
+        self.app.world.resource_mut::<NextState<GameState>>().set(state);
+    }
+
+    pub fn app_state(&self) -> AppState {
+        *self.app.world.resource::<State<AppState>>().get()
+    }
+
+    pub fn wave_state(&self) -> WaveState {
+        *self.app.world.resource::<State<WaveState>>().get()
+    }
+
+    pub fn game_state(&self) -> GameState {
+        *self.app.world.resource::<State<GameState>>().get()
+    }
+
+    // Only exists once the game has been setup
+    pub fn difficulty(&self) -> Option<&Difficulty> {
+        self.app.world.get_resource::<Difficulty>()
+    }
+
+    pub fn player(&mut self) -> Option<&Player> {
+        self.app.world.query::<&Player>().get_single(&self.app.world).ok()
+    }
+
+    // Every enemy and summon
+    pub fn characters(&mut self) -> Vec<&Character> {
+        self.app.world.query::<&Character>().iter(&self.app.world).collect()
+    }
+
+    // Moves the virtual cursor, None is the same as the cursor leaving the window
+    pub fn set_cursor_position(&mut self, position: Option<Vec2>) {
+        self.app.world.resource_mut::<Arena>().cursor_position = position.map(|position| position.extend(0.0));
+    }
+
+    // Keys and mouse buttons are sent as events like a window would, writing to ButtonInput directly
+    // would have just_pressed cleared by the input systems before the game reads it
+    pub fn press_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Pressed);
+    }
+
+    pub fn release_key(&mut self, key: KeyCode) {
+        self.send_key(key, ButtonState::Released);
+    }
+
+    fn send_key(&mut self, key: KeyCode, state: ButtonState) {
+        self.app.world.send_event(KeyboardInput {
+            key_code: key,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    // Gamepads are driven through the same events a real gamepad sends, so they go through bevy's gamepad settings
+    pub fn connect_gamepad(&mut self, gamepad: Gamepad) {
+        self.app.world.send_event(GamepadEvent::Connection(GamepadConnectionEvent::new(gamepad, GamepadConnection::Connected(GamepadInfo {
+            name: "Headless Gamepad".to_string(),
+        }))));
+    }
+
+    pub fn set_gamepad_button(&mut self, gamepad: Gamepad, button_type: GamepadButtonType, value: f32) {
+        self.app.world.send_event(GamepadEvent::Button(GamepadButtonChangedEvent::new(gamepad, button_type, value)));
+    }
+
+    pub fn set_gamepad_axis(&mut self, gamepad: Gamepad, axis_type: GamepadAxisType, value: f32) {
+        self.app.world.send_event(GamepadEvent::Axis(GamepadAxisChangedEvent::new(gamepad, axis_type, value)));
+    }
+
+    pub fn press_mouse_button(&mut self, button: MouseButton) {
+        self.send_mouse_button(button, ButtonState::Pressed);
+    }
+
+    pub fn release_mouse_button(&mut self, button: MouseButton) {
+        self.send_mouse_button(button, ButtonState::Released);
+    }
+
+    fn send_mouse_button(&mut self, button: MouseButton, state: ButtonState) {
+        self.app.world.send_event(MouseButtonInput {
+            button,
+            state,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+}
+
+impl Default for HeadlessGame {
+    fn default() -> Self {
+        HeadlessGame::new(HEADLESS_ARENA_SIZE)
+    }
+}
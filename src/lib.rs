@@ -0,0 +1,57 @@
+use bevy::prelude::*;
+
+pub mod art;
+pub mod ui;
+pub mod game;
+pub mod headless;
+
+#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
+pub enum AppState {
+    #[default]
+    Loading, // Waiting for the game content to load
+    MainMenu,
+    HighScores, // High score table opened from the main menu
+
+    GameSetup,
+    Game,
+    GameCleanup,
+}
+
+#[derive(Event)]
+pub struct GameCleanupEvent {
+    next_state: AppState,
+}
+
+// App states and the transitions between them, shared by the windowed and headless apps
+pub struct AppStatePlugin;
+
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<AppState>()
+            .add_event::<GameCleanupEvent>()
+            .add_systems(OnEnter(AppState::GameSetup), game_setup_transition)
+            .add_systems(OnEnter(AppState::GameCleanup), game_cleanup_transition);
+    }
+}
+
+// After the game is setup transition to AppState::Game
+fn game_setup_transition(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::Game);
+}
+
+// After cleanup transition enter the AppState given by the event
+fn game_cleanup_transition(
+    mut cleanup_event: EventReader<GameCleanupEvent>,
+    mut next_game_state: ResMut<NextState<game::GameState>>,
+    mut next_wave_state: ResMut<NextState<game::WaveState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+) {
+    next_game_state.set(game::GameState::Running);
+
+    // Leave the wave state now, otherwise leaving WaveState::Fight during the next game setup increases its difficulty
+    next_wave_state.set(game::WaveState::Start);
+    for event in cleanup_event.read() {
+        next_app_state.set(event.next_state);
+    }
+}
@@ -1,29 +1,32 @@
 use bevy::prelude::*;
 use bevy::window::{PrimaryWindow, WindowMode};
+use std::path::PathBuf;
 
-pub mod art;
-pub mod ui;
-pub mod game;
-#[derive(States, Debug, Clone, Copy, Eq, PartialEq, Hash, Default)]
-pub enum AppState {
-    #[default]
-    MainMenu,
-
-    GameSetup,
-    Game,
-    GameCleanup,
-}
-
-#[derive(Event)]
-pub struct GameCleanupEvent {
-    next_state: AppState,
-}
+use hexen_havoc::{art, ui, game, AppStatePlugin};
 
 fn main() {
     App::new()
-        .init_state::<AppState>()
-        .add_event::<GameCleanupEvent>()
-
+        .insert_resource(game::rng::SeedSettings {
+            seed: arg_value("--seed").map(|seed| seed.parse().expect("--seed must be a whole number")),
+        })
+        .insert_resource(game::replay::ReplaySettings {
+            record: arg_value("--record").is_some(),
+            record_path: arg_value("--record").map(PathBuf::from),
+            replay_path: arg_value("--replay").map(PathBuf::from),
+        })
+        .insert_resource(game::TickRate(
+            arg_value("--tick-rate").map(|tick_rate| tick_rate.parse().expect("--tick-rate must be a number")).unwrap_or(game::TICK_RATE),
+        ))
+        .insert_resource(game::high_scores::HighScoreSettings {
+            path: game::high_scores::default_path(),
+        })
+        .insert_resource(game::save::SaveSettings {
+            path: game::save::default_path(),
+        })
+        .insert_resource(game::keybinds::KeybindSettings {
+            path: game::keybinds::default_path(),
+        })
+        .insert_resource(game::summons::AutoSpend(arg_present("--auto-spend")))
         .add_plugins((
             DefaultPlugins.set(WindowPlugin { // Change window plugin to default to bordless fullscreen
                 primary_window: Some(Window {
@@ -36,14 +39,24 @@ fn main() {
         ))
 
 
-        .add_plugins((ui::UiPlugin, game::GamePlugin))
+        .add_plugins((AppStatePlugin, ui::UiPlugin, game::GamePlugin))
 
         .add_systems(Startup, (spawn_camera, spawn_background))
-        .add_systems(OnEnter(AppState::GameSetup), game_setup_transition)
-        .add_systems(OnEnter(AppState::GameCleanup), game_cleanup_transition)
         .run();
 }
 
+// Returns the value after a command line argument, eg. --seed 1234
+fn arg_value(name: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    let index = args.iter().position(|arg| arg == name)?;
+    args.get(index + 1).cloned()
+}
+
+// Returns true if a command line flag was given, eg. --auto-spend
+fn arg_present(name: &str) -> bool {
+    std::env::args().any(|arg| arg == name)
+}
+
 fn spawn_background(mut commands: Commands, asset_server: Res<AssetServer>, window_query: Query<&Window, With<PrimaryWindow>>) {
     let window = window_query.get_single().unwrap();
 
@@ -77,20 +90,3 @@ fn spawn_camera(mut commands: Commands, window_query: Query<&Window, With<Primar
         }
     );
 }
-
-// After the game is setup transition to AppState::Game
-fn game_setup_transition(mut next_state: ResMut<NextState<AppState>>) {
-    next_state.set(AppState::Game);
-}
-
-// After cleanup transition enter the AppState given by the event
-fn game_cleanup_transition(
-    mut cleanup_event: EventReader<GameCleanupEvent>,
-    mut next_game_state: ResMut<NextState<game::GameState>>,
-    mut next_app_state: ResMut<NextState<AppState>>,
-) {
-    next_game_state.set(game::GameState::Running);
-    for event in cleanup_event.read() {
-        next_app_state.set(event.next_state);
-    }
-}
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+
+use crate::game::keybinds::{self, Keybinds, KeybindSettings, RebindState, Rebinding};
+use super::layout;
+
+// Open the controls menu
+// This code is here to avoid duplicates, since there is a controls button in the main and pause menu
+pub fn controls_button_interactions(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::ControlsButton>)>,
+    mut next_state: ResMut<NextState<RebindState>>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            next_state.set(RebindState::Open);
+        }
+    }
+}
+
+// Listen for a new binding for the action that was clicked
+pub fn binding_button_interactions(
+    button_query: Query<(&Interaction, &layout::BindingButton), Changed<Interaction>>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, binding_button) in button_query.iter() {
+        if interaction == &Interaction::Pressed {
+            rebinding.action = Some(binding_button.0);
+            rebinding.conflict = None;
+        }
+    }
+}
+
+// Go back to the default bindings
+pub fn reset_button_interactions(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::ResetButton>)>,
+    settings: Res<KeybindSettings>,
+    mut keybinds: ResMut<Keybinds>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            *keybinds = Keybinds::default();
+            *rebinding = Rebinding::default();
+            keybinds::save_keybinds(&settings, &keybinds);
+        }
+    }
+}
+
+// Close the controls menu, going back to the menu it was opened from
+pub fn back_button_interactions(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::BackButton>)>,
+    mut next_state: ResMut<NextState<RebindState>>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            next_state.set(RebindState::Closed);
+        }
+    }
+}
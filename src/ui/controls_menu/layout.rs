@@ -0,0 +1,210 @@
+use bevy::prelude::*;
+
+use crate::ui;
+use crate::game::keybinds::Action;
+use ui::{helpers, styles};
+
+#[derive(Component)]
+pub struct ControlsMenu;
+
+// Opens the controls menu, there is one in the main menu and the pause menu
+#[derive(Component)]
+pub struct ControlsButton;
+
+// Rebinds this action when pressed
+#[derive(Component)]
+pub struct BindingButton(pub Action);
+
+// Key or mouse button bound to this action
+#[derive(Component)]
+pub struct BindingText(pub Action);
+
+// Tells the player what to press, or why a binding couldn't be used
+#[derive(Component)]
+pub struct ControlsStatusText;
+
+#[derive(Component)]
+pub struct ResetButton;
+
+#[derive(Component)]
+pub struct BackButton;
+
+pub const ACTIONS_PER_COLUMN: usize = 9;
+
+pub fn spawn_controls_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(
+
+        (
+            ControlsMenu,
+
+            // Drawn over the main menu or pause menu it was opened from
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+
+                    row_gap: Val::Percent(1.0),
+
+                    ..default()
+                },
+                background_color: Color::hex(styles::BACKGROUND_HEX).unwrap().into(),
+                z_index: ZIndex::Global(styles::CONTROLS_Z_INDEX),
+                ..default()
+            }
+        )
+    )
+
+    .with_children(|parent| {
+
+        // Title
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    margin: UiRect {
+                        bottom: Val::Percent(2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            }
+        ).with_children(|parent| {
+            parent.spawn(
+                helpers::text("Controls", styles::TITLE_TEXT_STYLE, &asset_server)
+            );
+        });
+
+        parent.spawn(
+            (
+                ControlsStatusText,
+                helpers::text("", styles::REWARD_DESCRIPTION_TEXT_STYLE, &asset_server)
+            )
+        );
+
+        // Actions are split into columns so they fit on the screen
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    width: Val::Percent(90.0),
+                    column_gap: Val::Percent(4.0),
+                    ..default()
+                },
+                ..default()
+            }
+        ).with_children(|parent| {
+            for column in Action::all().chunks(ACTIONS_PER_COLUMN) {
+                parent.spawn(
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            width: Val::Percent(45.0),
+                            row_gap: Val::Px(8.0),
+                            ..default()
+                        },
+                        ..default()
+                    }
+                ).with_children(|parent| {
+                    for action in column {
+                        spawn_binding_row(parent, *action, &asset_server);
+                    }
+                });
+            }
+        });
+
+        // Reset and back buttons
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(8.0),
+                    column_gap: Val::Percent(2.0),
+                    margin: UiRect {
+                        top: Val::Percent(2.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            }
+        ).with_children(|parent| {
+            parent.spawn(
+                (
+                    ui::GenericButton,
+                    ResetButton,
+                    helpers::button(styles::CONTROLS_BUTTON_STYLE),
+                )
+            )
+            .with_children(|parent| {
+                parent.spawn(
+                    helpers::text("Reset", styles::CONTROLS_BUTTON_STYLE.text_style.unwrap(), &asset_server)
+                );
+            });
+
+            parent.spawn(
+                (
+                    ui::GenericButton,
+                    BackButton,
+                    helpers::button(styles::CONTROLS_BUTTON_STYLE),
+                )
+            )
+            .with_children(|parent| {
+                parent.spawn(
+                    helpers::text("Back", styles::CONTROLS_BUTTON_STYLE.text_style.unwrap(), &asset_server)
+                );
+            });
+        });
+    });
+}
+
+// Action name on the left, and a button with it's binding on the right
+fn spawn_binding_row(parent: &mut ChildBuilder, action: Action, asset_server: &AssetServer) {
+    parent.spawn(
+        NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::SpaceBetween,
+                align_items: AlignItems::Center,
+                width: Val::Percent(100.0),
+                height: Val::Px(44.0),
+                ..default()
+            },
+            ..default()
+        }
+    ).with_children(|parent| {
+        parent.spawn(
+            helpers::text(&action.name(), styles::REWARD_DESCRIPTION_TEXT_STYLE, asset_server)
+        );
+
+        parent.spawn(
+            (
+                ui::GenericButton,
+                BindingButton(action),
+                helpers::button(styles::BINDING_BUTTON_STYLE),
+            )
+        )
+        .with_children(|parent| {
+            parent.spawn(
+                (
+                    BindingText(action),
+                    helpers::text("", styles::BINDING_BUTTON_STYLE.text_style.unwrap(), asset_server)
+                )
+            );
+        });
+    });
+}
+
+pub fn despawn_controls_menu(mut commands: Commands, controls_menu_query: Query<Entity, With<ControlsMenu>>) {
+    if let Ok(controls_menu_entity) = controls_menu_query.get_single() {
+        commands.entity(controls_menu_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+
+use crate::AppState;
+use crate::game::GameState;
+use crate::game::keybinds::{Keybinds, RebindState, Rebinding};
+
+pub mod layout;
+pub mod interactions;
+
+pub struct ControlsMenuPlugin;
+
+impl Plugin for ControlsMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(RebindState::Open), layout::spawn_controls_menu)
+            .add_systems(OnExit(RebindState::Open), layout::despawn_controls_menu)
+
+            // The controls menu closes with the menu it was opened from
+            .add_systems(OnExit(AppState::MainMenu), close_controls_menu)
+            .add_systems(OnExit(GameState::Paused), close_controls_menu)
+
+            .add_systems(Update, interactions::controls_button_interactions)
+            .add_systems(Update, (interactions::binding_button_interactions, interactions::reset_button_interactions, interactions::back_button_interactions, update_controls_text).run_if(in_state(RebindState::Open)));
+    }
+}
+
+fn close_controls_menu(mut next_state: ResMut<NextState<RebindState>>) {
+    next_state.set(RebindState::Closed);
+}
+
+// Shows the binding of every action, and what the player needs to do next
+fn update_controls_text(
+    mut binding_text_query: Query<(&mut Text, &layout::BindingText), Without<layout::ControlsStatusText>>,
+    mut status_text_query: Query<&mut Text, With<layout::ControlsStatusText>>,
+    keybinds: Res<Keybinds>,
+    rebinding: Res<Rebinding>,
+    added_query: Query<(), Added<layout::ControlsMenu>>,
+) {
+    if !keybinds.is_changed() && !rebinding.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    for (mut text, binding_text) in binding_text_query.iter_mut() {
+        text.sections[0].value = if rebinding.action == Some(binding_text.0) {
+            "...".to_string()
+        } else {
+            match keybinds.0.get(&binding_text.0) {
+                Some(binding) => binding.to_string(),
+                None => "Unbound".to_string(),
+            }
+        };
+    }
+
+    if let Ok(mut text) = status_text_query.get_single_mut() {
+        text.sections[0].value = match (rebinding.action, rebinding.conflict) {
+            (Some(action), _) => format!("Press a key or mouse button for {}", action.name()),
+            (None, Some((binding, other))) => format!("{} is already used by {}", binding, other.name()),
+            (None, None) => "Click an action to change it's binding".to_string(),
+        };
+    }
+}
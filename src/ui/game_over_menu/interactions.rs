@@ -10,12 +10,9 @@ pub fn restart_button_interactions(
     mut cleanup_event: EventWriter<GameCleanupEvent>,
 ) {
     if let Ok(interaction) = button_query.get_single() {
-        match interaction {
-            Interaction::Pressed => {
-                cleanup_event.send(GameCleanupEvent{next_state: AppState::GameSetup}); // Cleanup the game and then setup the game to restart
-                next_state.set(AppState::GameCleanup);
-            },
-            _ => (), 
+        if interaction == &Interaction::Pressed {
+            cleanup_event.send(GameCleanupEvent{next_state: AppState::GameSetup}); // Cleanup the game and then setup the game to restart
+            next_state.set(AppState::GameCleanup);
         }
     }
 }
\ No newline at end of file
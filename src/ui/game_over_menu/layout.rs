@@ -9,8 +9,14 @@ pub struct GameOverMenu;
 #[derive(Component)]
 pub struct RestartButton;
 
-pub fn spawn_game_over_menu(mut commands: Commands, asset_server: Res<AssetServer>, difficulty: Res<game::Difficulty>) {
+pub fn spawn_game_over_menu(mut commands: Commands, asset_server: Res<AssetServer>, difficulty: Res<game::Difficulty>, game_seed: Res<game::rng::GameSeed>, score: Res<game::score::Score>, high_scores: Res<game::high_scores::HighScores>) {
     let wave_text = &format!("You reached wave: {}", difficulty.wave);
+    let score_text = &format!("Score: {}", score.total());
+    let score_breakdown_text = &format!(
+        "Kills {}    Waves {}    Collectibles {}    Time {}    Best streak {}",
+        score.kill_points, score.wave_points, score.collectible_points, score.time_points, score.best_streak,
+    );
+    let seed_text = &format!("Seed: {}", game_seed.0);
 
     commands.spawn(
         (
@@ -30,6 +36,7 @@ pub fn spawn_game_over_menu(mut commands: Commands, asset_server: Res<AssetServe
                     ..default()
                 },
                 background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                z_index: ZIndex::Global(styles::MENU_Z_INDEX),
                 ..default()
             }
         )
@@ -60,6 +67,25 @@ pub fn spawn_game_over_menu(mut commands: Commands, asset_server: Res<AssetServe
         parent.spawn(
             helpers::text(wave_text, styles::TITLE_TEXT_STYLE, &asset_server)
         );
+
+        // Score and where the points came from
+        parent.spawn(
+            helpers::text(score_text, styles::BODY_TEXT_STYLE, &asset_server)
+        );
+        parent.spawn(
+            helpers::text(score_breakdown_text, styles::HINT_TEXT_STYLE, &asset_server)
+        );
+
+        if let Some(rank) = high_scores.last_run_rank {
+            parent.spawn(
+                helpers::text(&format!("New high score! #{}", rank + 1), styles::BODY_TEXT_STYLE, &asset_server)
+            );
+        }
+
+        // Seed so the run can be played again
+        parent.spawn(
+            helpers::text(seed_text, styles::BODY_TEXT_STYLE, &asset_server)
+        );
         
         // Restart game button
         parent.spawn(
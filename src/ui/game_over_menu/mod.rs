@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::game::GameState;
+use crate::game::{high_scores, GameState};
 
 pub mod layout;
 pub mod interactions;
@@ -10,7 +10,7 @@ pub struct GameOverMenuPlugin;
 impl Plugin for GameOverMenuPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(OnEnter(GameState::GameOver), layout::spawn_game_over_menu)
+            .add_systems(OnEnter(GameState::GameOver), layout::spawn_game_over_menu.after(high_scores::record_high_score))
             .add_systems(OnExit(GameState::GameOver), layout::despawn_game_over_menu)
             .add_systems(Update, interactions::restart_button_interactions.run_if(in_state(GameState::GameOver)));
     }
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use crate::AppState;
+use super::layout;
+
+// Go back to the main menu, the high score table isn't part of a game so there is nothing to cleanup
+pub fn back_button_interactions(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::BackButton>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use bevy::prelude::*;
+
+use crate::ui;
+use crate::game::high_scores;
+use ui::{helpers, styles};
+
+#[derive(Component)]
+pub struct HighScoreMenu;
+
+#[derive(Component)]
+pub struct BackButton;
+
+pub fn spawn_high_score_menu(mut commands: Commands, asset_server: Res<AssetServer>, high_scores: Res<high_scores::HighScores>) {
+    commands.spawn(
+
+        (
+            HighScoreMenu,
+
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+
+                    row_gap: Val::Percent(1.0),
+
+                    ..default()
+                },
+                background_color: Color::hex(styles::BACKGROUND_HEX).unwrap().into(),
+                ..default()
+            }
+        )
+    )
+
+    .with_children(|parent| {
+
+        // Title
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    margin: UiRect {
+                        bottom: Val::Percent(5.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..default()
+            }
+        ).with_children(|parent| {
+            parent.spawn(
+                helpers::text("High Scores", styles::TITLE_TEXT_STYLE, &asset_server)
+            );
+        });
+
+        if high_scores.high_scores.is_empty() {
+            parent.spawn(
+                helpers::text("No runs yet", styles::BODY_TEXT_STYLE, &asset_server)
+            );
+        }
+
+        // One row for every run in the table
+        for (i, high_score) in high_scores.high_scores.iter().enumerate() {
+            let summons: Vec<String> = high_score.summons.iter()
+                .map(|(name, count)| format!("{} x{}", name, count))
+                .collect();
+
+            let row_text = format!(
+                "{}.  {}    Wave {}    Seed {}    {}    {}",
+                i + 1, high_score.score, high_score.wave, high_score.seed, high_score.date, summons.join(", "),
+            );
+
+            parent.spawn(
+                helpers::text(&row_text, styles::REWARD_DESCRIPTION_TEXT_STYLE, &asset_server)
+            );
+        }
+
+        // Back to main menu button
+        parent.spawn(
+            (
+                ui::GenericButton,
+                BackButton,
+                helpers::button(styles::BUTTON_STYLE),
+            )
+        )
+        .with_children(|parent| {
+            parent.spawn(
+                helpers::text("Back", styles::BUTTON_STYLE.text_style.unwrap(), &asset_server)
+            );
+        });
+    });
+}
+
+pub fn despawn_high_score_menu(mut commands: Commands, high_score_menu_query: Query<Entity, With<HighScoreMenu>>) {
+    if let Ok(high_score_menu_entity) = high_score_menu_query.get_single() {
+        commands.entity(high_score_menu_entity).despawn_recursive();
+    }
+}
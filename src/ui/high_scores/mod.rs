@@ -0,0 +1,16 @@
+use bevy::prelude::*;
+use crate::AppState;
+
+pub mod layout;
+pub mod interactions;
+
+pub struct HighScoreMenuPlugin;
+
+impl Plugin for HighScoreMenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::HighScores), layout::spawn_high_score_menu)
+            .add_systems(OnExit(AppState::HighScores), layout::despawn_high_score_menu)
+            .add_systems(Update, interactions::back_button_interactions.run_if(in_state(AppState::HighScores)));
+    }
+}
@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use crate::art;
-use crate::ui::{styles, helpers};
+use crate::ui::{self, styles, helpers};
+use crate::game::{characters, keybinds, summons};
 
 #[derive(Component)]
 pub struct Hud;
@@ -8,7 +9,51 @@ pub struct Hud;
 #[derive(Component)]
 pub struct HealthText;
 
-pub fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[derive(Component)]
+pub struct SentryText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+// Time until the player can dash again
+#[derive(Component)]
+pub struct DashText;
+
+// Boss name and health bar, hidden when there isn't a boss
+#[derive(Component)]
+pub struct BossBar;
+
+#[derive(Component)]
+pub struct BossNameText;
+
+// Width is the fraction of health the boss has left
+#[derive(Component)]
+pub struct BossHealthFill;
+
+// Shows where the gamepad cursor is, hidden while the mouse is used
+#[derive(Component)]
+pub struct GamepadCursorNode;
+
+// Ammount of the collectible type at this index the player has
+#[derive(Component)]
+pub struct CollectibleText(pub usize);
+
+// Name of the summon in a summon bar slot, with the key that buys it
+#[derive(Component)]
+pub struct SummonNameText(pub usize);
+
+// Cost of the summon in a summon bar slot, greyed out when the player can't afford it
+#[derive(Component)]
+pub struct SummonCostText(pub usize);
+
+pub fn spawn_hud(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    auto_spend: Res<summons::AutoSpend>,
+    summon_types: Res<characters::SummonTypes>,
+    collectible_types: Res<characters::CollectibleTypes>,
+    keybinds: Res<keybinds::Keybinds>,
+) {
     commands.spawn(
         
         (
@@ -62,11 +107,219 @@ pub fn spawn_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
                 helpers::text("0", styles::BODY_TEXT_STYLE, &asset_server)
             )
         );
+
+        // Score and kill streak multiplier
+        parent.spawn(
+            (
+                ScoreText,
+                helpers::text("Score: 0", styles::BODY_TEXT_STYLE, &asset_server).with_style(Style {
+                    margin: UiRect::left(Val::Px(32.0)),
+                    ..default()
+                })
+            )
+        );
+
+        // Dash cooldown
+        parent.spawn(
+            (
+                DashText,
+                helpers::text("Dash: Ready", styles::BODY_TEXT_STYLE, &asset_server).with_style(Style {
+                    margin: UiRect::left(Val::Px(32.0)),
+                    ..default()
+                })
+            )
+        );
+
+        // Collectible images and counts
+        for (i, collectible_info) in collectible_types.0.iter().enumerate() {
+            parent.spawn(
+                ImageBundle {
+                    image: UiImage::new(asset_server.load(collectible_info.sprite_information.sprite_path.to_string())),
+                    style: Style {
+                        margin: UiRect::left(Val::Px(32.0)),
+                        ..default()
+                    },
+                    ..default()
+                }
+            );
+            parent.spawn(
+                (
+                    CollectibleText(i),
+                    helpers::text("0", styles::BODY_TEXT_STYLE, &asset_server)
+                )
+            );
+        }
+
+        // Shows how to place sentries and what they cost
+        parent.spawn(
+            (
+                SentryText,
+                helpers::text("", styles::HINT_TEXT_STYLE, &asset_server).with_style(Style {
+                    margin: UiRect::left(Val::Px(32.0)),
+                    ..default()
+                })
+            )
+        );
+    });
+
+    // Boss health bar accross the top of the screen, below the rest of the hud
+    commands.spawn(
+        (
+            Hud,
+            BossBar,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(6.0),
+                    left: Val::Percent(25.0),
+
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(50.0),
+                    row_gap: Val::Px(4.0),
+
+                    ..default()
+                },
+                visibility: Visibility::Hidden,
+                ..default()
+            }
+        )
+    )
+    .with_children(|parent| {
+        parent.spawn(
+            (
+                BossNameText,
+                helpers::text("", styles::HINT_TEXT_STYLE, &asset_server)
+            )
+        );
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(16.0),
+                    ..default()
+                },
+                background_color: Color::hex(styles::FOREGROUND_HEX).unwrap().into(),
+                ..default()
+            }
+        ).with_children(|parent| {
+            parent.spawn(
+                (
+                    BossHealthFill,
+                    NodeBundle {
+                        style: Style {
+                            width: Val::Percent(100.0),
+                            height: Val::Percent(100.0),
+                            ..default()
+                        },
+                        background_color: Color::hex(styles::BOSS_HEALTH_HEX).unwrap().into(),
+                        ..default()
+                    }
+                )
+            );
+        });
+    });
+
+    commands.spawn(
+        (
+            Hud,
+            GamepadCursorNode,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(styles::GAMEPAD_CURSOR_SIZE),
+                    height: Val::Px(styles::GAMEPAD_CURSOR_SIZE),
+                    ..default()
+                },
+                background_color: Color::hex(styles::TEXT_BOLD_HEX).unwrap().into(),
+                visibility: Visibility::Hidden,
+                z_index: ZIndex::Global(styles::CURSOR_Z_INDEX),
+                ..default()
+            }
+        )
+    );
+
+    if !auto_spend.0 {
+        spawn_summon_bar(&mut commands, &asset_server, &summon_types, &collectible_types, &keybinds);
+    }
+}
+
+// Bar accross the bottom of the screen with a slot for every summon, summons are bought by clicking their slot or pressing it's hotkey
+fn spawn_summon_bar(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    summon_types: &characters::SummonTypes,
+    collectible_types: &characters::CollectibleTypes,
+    keybinds: &keybinds::Keybinds,
+) {
+    commands.spawn(
+        (
+            Hud,
+
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Px(0.0),
+
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(10.0),
+
+                    column_gap: Val::Percent(1.0),
+
+                    ..default()
+                },
+                ..default()
+            }
+        )
+    )
+
+    .with_children(|parent| {
+        for (i, summon_info) in summon_types.0.iter().enumerate() {
+            let name = summon_slot_name(i, summon_info, keybinds);
+            let cost: Vec<String> = summon_info.cost()
+                .map(|(collectible_type, ammount)| format!("{} {}", ammount, collectible_types.0[collectible_type].name))
+                .collect();
+
+            parent.spawn(
+                (
+                    ui::GenericButton,
+                    summons::SummonSlot(i),
+                    helpers::button(styles::SUMMON_SLOT_STYLE),
+                )
+            )
+            .with_children(|parent| {
+                parent.spawn(
+                    (
+                        SummonNameText(i),
+                        helpers::text(&name, styles::SUMMON_SLOT_STYLE.text_style.unwrap(), asset_server)
+                    )
+                );
+                parent.spawn(
+                    (
+                        SummonCostText(i),
+                        helpers::text(&cost.join(", "), styles::SUMMON_SLOT_STYLE.text_style.unwrap(), asset_server)
+                    )
+                );
+            });
+        }
     });
 }
 
+// Summon name with the key bound to it's slot, eg. 1 Eye
+pub fn summon_slot_name(slot: usize, summon_info: &characters::CharacterInfo, keybinds: &keybinds::Keybinds) -> String {
+    match keybinds.0.get(&keybinds::Action::BuySummon(slot)) {
+        Some(binding) => format!("{} {}", binding, summon_info.name),
+        None => summon_info.name.clone(),
+    }
+}
+
 pub fn despawn_hud(mut commands: Commands, hud_query: Query<Entity, With<Hud>>) {
-    if let Ok(hud_entity) = hud_query.get_single() {
+    for hud_entity in hud_query.iter() {
         commands.entity(hud_entity).despawn_recursive();
     }
 }
\ No newline at end of file
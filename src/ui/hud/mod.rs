@@ -1,6 +1,7 @@
 use bevy::prelude::*;
 use crate::AppState;
-use crate::game::{player, GameState};
+use crate::ui::styles;
+use crate::game::{player, characters, enemies, keybinds, score, gamepad, Arena, GameState};
 
 pub mod layout;
 
@@ -11,7 +12,27 @@ impl Plugin for HudPlugin {
         app
             .add_systems(OnEnter(AppState::Game), layout::spawn_hud)
             .add_systems(OnExit(AppState::Game), layout::despawn_hud)
-            .add_systems(Update, update_health_text.run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+            .add_systems(Update, update_gamepad_cursor.run_if(in_state(AppState::Game)))
+            .add_systems(Update, (update_health_text, update_score_text.run_if(resource_changed::<score::Score>), update_dash_text, update_collectible_text, update_sentry_text, update_summon_costs, update_summon_names, update_boss_bar).run_if(in_state(AppState::Game)).run_if(in_state(GameState::Running)));
+    }
+}
+
+// Moves the cursor node to the gamepad cursor, the node is centered on the cursor
+fn update_gamepad_cursor(
+    mut cursor_query: Query<(&mut Style, &mut Visibility), With<layout::GamepadCursorNode>>,
+    gamepad_cursor: Res<gamepad::GamepadCursor>,
+    arena: Res<Arena>,
+) {
+    if let Ok((mut style, mut visibility)) = cursor_query.get_single_mut() {
+        if !gamepad_cursor.active {
+            visibility.set_if_neq(Visibility::Hidden);
+            return;
+        }
+
+        // World coordinates start at the bottom left, ui coordinates start at the top left
+        visibility.set_if_neq(Visibility::Visible);
+        style.left = Val::Px(gamepad_cursor.position.x - styles::GAMEPAD_CURSOR_SIZE / 2.0);
+        style.top = Val::Px(arena.height - gamepad_cursor.position.y - styles::GAMEPAD_CURSOR_SIZE / 2.0);
     }
 }
 
@@ -24,4 +45,181 @@ fn update_health_text(
             text.sections[0].value = format!("{}", player.health);
         }
     }
-}
\ No newline at end of file
+}
+
+fn update_score_text(
+    mut text_query: Query<&mut Text, With<layout::ScoreText>>,
+    score: Res<score::Score>,
+) {
+    if let Ok(mut text) = text_query.get_single_mut() {
+        let value = match score.streak_multiplier() {
+            1 => format!("Score: {}", score.total()),
+            multiplier => format!("Score: {} x{}", score.total(), multiplier),
+        };
+
+        if text.sections[0].value != value {
+            text.sections[0].value = value;
+        }
+    }
+}
+
+fn update_dash_text(
+    mut text_query: Query<&mut Text, With<layout::DashText>>,
+    player_query: Query<&player::Player, Changed<player::Player>>,
+) {
+    if let Ok(mut text) = text_query.get_single_mut() {
+        if let Ok(player) = player_query.get_single() {
+            let value = match player.dash_cooldown > 0.0 {
+                true => format!("Dash: {:.1}", player.dash_cooldown),
+                false => "Dash: Ready".to_string(),
+            };
+
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+    }
+}
+
+// Shows the boss's name and health, the bar is hidden when there isn't a boss
+fn update_boss_bar(
+    mut bar_query: Query<&mut Visibility, With<layout::BossBar>>,
+    mut fill_query: Query<&mut Style, With<layout::BossHealthFill>>,
+    mut text_query: Query<&mut Text, With<layout::BossNameText>>,
+    boss_query: Query<&characters::Character, With<enemies::Boss>>,
+    enemy_types: Res<characters::EnemyTypes>,
+) {
+    let Ok(mut visibility) = bar_query.get_single_mut() else {
+        return;
+    };
+    let Ok(boss) = boss_query.get_single() else {
+        visibility.set_if_neq(Visibility::Hidden);
+        return;
+    };
+    visibility.set_if_neq(Visibility::Inherited);
+
+    let boss_info = &enemy_types.0[boss.type_index];
+    if let Ok(mut style) = fill_query.get_single_mut() {
+        let health = (boss.health as f32 / boss_info.max_health as f32).clamp(0.0, 1.0); // Wave modifiers can give the boss more than it's max health
+        let width = Val::Percent(health * 100.0);
+        if style.width != width {
+            style.width = width;
+        }
+    }
+    if let Ok(mut text) = text_query.get_single_mut() {
+        if text.sections[0].value != boss_info.name {
+            text.sections[0].value = boss_info.name.clone();
+        }
+    }
+}
+
+// Shows each collectible count with the ammount the cheapest summon that costs it needs, eg. 3/5
+// Text is only set when it changes, the player changes every tick while it is moving
+fn update_collectible_text(
+    mut text_query: Query<(&mut Text, &layout::CollectibleText)>,
+    player_query: Query<&player::Player, Changed<player::Player>>,
+    summon_types: Res<characters::SummonTypes>,
+) {
+    if let Ok(player) = player_query.get_single() {
+        for (mut text, collectible_text) in text_query.iter_mut() {
+            let collectible_type = collectible_text.0;
+            let ammount = player.collectibles[collectible_type];
+
+            let next_summon_cost = summon_types.0.iter()
+                .flat_map(|summon_info| summon_info.cost())
+                .filter(|(cost_type, _)| *cost_type == collectible_type)
+                .map(|(_, cost)| cost)
+                .min();
+
+            let value = match next_summon_cost {
+                Some(cost) => format!("{}/{}", ammount, cost),
+                None => format!("{}", ammount),
+            };
+
+            if text.sections[0].value != value {
+                text.sections[0].value = value;
+            }
+        }
+    }
+}
+
+// Also updated when the keybinds change, they can be changed from the pause menu
+fn update_sentry_text(
+    mut text_query: Query<&mut Text, With<layout::SentryText>>,
+    player_query: Query<Ref<player::Player>>,
+    summon_types: Res<characters::SummonTypes>,
+    collectible_types: Res<characters::CollectibleTypes>,
+    keybinds: Res<keybinds::Keybinds>,
+) {
+    if let Ok(mut text) = text_query.get_single_mut() {
+        if let Ok(player) = player_query.get_single() {
+            if !player.is_changed() && !keybinds.is_changed() {
+                return;
+            }
+
+            let key = match keybinds.0.get(&keybinds::Action::PlaceSentry) {
+                Some(binding) => binding.to_string(),
+                None => "Unbound".to_string(),
+            };
+
+            let mut hints = Vec::new();
+            for (i, summon_info) in summon_types.0.iter().enumerate() {
+                if summon_info.summon_type != Some(characters::SummonType::Sentry) {
+                    continue;
+                }
+
+                let cost: Vec<String> = summon_info.cost()
+                    .map(|(collectible_type, ammount)| format!("{} {}", ammount, collectible_types.0[collectible_type].name))
+                    .collect();
+                let ready = player.sentries.iter().filter(|sentry| **sentry == i).count();
+
+                hints.push(format!("{} ({}): {} ready", summon_info.name, cost.join(", "), ready));
+            }
+
+            if hints.is_empty() {
+                text.sections[0].value = String::new();
+            } else {
+                text.sections[0].value = format!("{} - place sentry at mouse    {}", key, hints.join("    "));
+            }
+        }
+    }
+}
+
+// Keeps the key shown in every summon bar slot up to date with the keybinds
+fn update_summon_names(
+    mut text_query: Query<(&mut Text, &layout::SummonNameText)>,
+    summon_types: Res<characters::SummonTypes>,
+    keybinds: Res<keybinds::Keybinds>,
+) {
+    if !keybinds.is_changed() {
+        return;
+    }
+
+    for (mut text, summon_name_text) in text_query.iter_mut() {
+        if let Some(summon_info) = summon_types.0.get(summon_name_text.0) {
+            text.sections[0].value = layout::summon_slot_name(summon_name_text.0, summon_info, &keybinds);
+        }
+    }
+}
+
+// Greys out the cost of summons the player can't afford
+fn update_summon_costs(
+    mut text_query: Query<(&mut Text, &layout::SummonCostText)>,
+    player_query: Query<&player::Player, Changed<player::Player>>,
+    summon_types: Res<characters::SummonTypes>,
+) {
+    if let Ok(player) = player_query.get_single() {
+        for (mut text, summon_cost_text) in text_query.iter_mut() {
+            let color_hex = if summon_types.0[summon_cost_text.0].affordable(&player.collectibles) {
+                styles::TEXT_BOLD_HEX
+            } else {
+                styles::TEXT_DISABLED_HEX
+            };
+            let color = Color::hex(color_hex).unwrap();
+
+            if text.sections[0].style.color != color {
+                text.sections[0].style.color = color;
+            }
+        }
+    }
+}
@@ -1,7 +1,21 @@
 use bevy::prelude::*;
-use crate::AppState;
+use crate::{AppState, game};
 use super::layout;
 
+// Continue the saved run when the continue button is pressed
+pub fn continue_button_interactions(
+    mut commands: Commands,
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::ContinueButton>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            commands.insert_resource(game::save::PendingResume);
+            next_state.set(AppState::GameSetup)
+        }
+    }
+}
+
 // Start the game when the play button is pressed
 pub fn play_button_interactions(
     button_query: Query<&Interaction, (Changed<Interaction>, With<layout::PlayButton>)>,
@@ -14,6 +28,18 @@ pub fn play_button_interactions(
     }
 }
 
+// Show the high score table
+pub fn high_scores_button_interactions(
+    button_query: Query<&Interaction, (Changed<Interaction>, With<layout::HighScoresButton>)>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if let Ok(interaction) = button_query.get_single() {
+        if interaction == &Interaction::Pressed {
+            next_state.set(AppState::HighScores)
+        }
+    }
+}
+
 // Send exit app event when the quit button is pressed
 pub fn quit_button_interactions(
     button_query: Query<&Interaction, (Changed<Interaction>, With<layout::QuitButton>)>,
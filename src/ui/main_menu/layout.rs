@@ -1,18 +1,24 @@
 use bevy::prelude::*;
-use crate::ui;
+use crate::{ui, game};
 use ui::{styles, helpers};
 
 #[derive(Component)]
 pub struct MainMenu;
 
 // Components for individual buttons
+#[derive(Component)]
+pub struct ContinueButton;
+
 #[derive(Component)]
 pub struct PlayButton;
 
+#[derive(Component)]
+pub struct HighScoresButton;
+
 #[derive(Component)]
 pub struct QuitButton;
 
-pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>, saved_run: Res<game::save::SavedRun>) {
     commands.spawn(
 
         // Main menu background, and parent node
@@ -59,6 +65,22 @@ pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
             );
         });
         
+        // Continue button, only shown if there is a saved run
+        if saved_run.save.is_some() {
+            parent.spawn(
+                (
+                    ui::GenericButton,
+                    ContinueButton,
+                    helpers::button(styles::BUTTON_STYLE),
+                )
+            )
+            .with_children(|parent| {
+                parent.spawn(
+                    helpers::text("Continue", styles::BUTTON_STYLE.text_style.unwrap(), &asset_server)
+                );
+            });
+        }
+
         // Play button
         parent.spawn(
             (
@@ -73,6 +95,34 @@ pub fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
             );
         });
 
+        // High scores button
+        parent.spawn(
+            (
+                ui::GenericButton,
+                HighScoresButton,
+                helpers::button(styles::BUTTON_STYLE),
+            )   
+        )
+        .with_children(|parent| {
+            parent.spawn(
+                helpers::text("High Scores", styles::BUTTON_STYLE.text_style.unwrap(), &asset_server)
+            );
+        });
+
+        // Controls button
+        parent.spawn(
+            (
+                ui::GenericButton,
+                ui::controls_menu::layout::ControlsButton,
+                helpers::button(styles::BUTTON_STYLE),
+            )   
+        )
+        .with_children(|parent| {
+            parent.spawn(
+                helpers::text("Controls", styles::BUTTON_STYLE.text_style.unwrap(), &asset_server)
+            );
+        });
+
         // Quit button
         parent.spawn(
             (
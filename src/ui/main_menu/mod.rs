@@ -11,6 +11,6 @@ impl Plugin for MainMenuPlugin {
         app
             .add_systems(OnEnter(AppState::MainMenu), layout::spawn_main_menu)
             .add_systems(OnExit(AppState::MainMenu), layout::despawn_main_menu)
-            .add_systems(Update, (interactions::play_button_interactions, interactions::quit_button_interactions).run_if(in_state(AppState::MainMenu)));
+            .add_systems(Update, (interactions::continue_button_interactions, interactions::play_button_interactions, interactions::high_scores_button_interactions, interactions::quit_button_interactions).run_if(in_state(AppState::MainMenu)));
     }
 }
\ No newline at end of file
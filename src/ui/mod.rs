@@ -2,12 +2,16 @@ use bevy::prelude::*;
 
 pub mod styles;
 pub mod helpers;
+pub mod navigation;
 
 pub mod main_menu;
 pub mod hud;
 pub mod pause_menu;
 pub mod wave_start;
+pub mod wave_reward;
 pub mod game_over_menu;
+pub mod high_scores;
+pub mod controls_menu;
 
 use crate::{GameCleanupEvent, AppState};
 
@@ -22,12 +26,14 @@ pub struct UiPlugin;
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_plugins((main_menu::MainMenuPlugin, hud::HudPlugin, pause_menu::PauseMenuPlugin, wave_start::WaveStartPlugin, game_over_menu::GameOverMenuPlugin))
+        .add_plugins((main_menu::MainMenuPlugin, hud::HudPlugin, pause_menu::PauseMenuPlugin, wave_start::WaveStartPlugin, wave_reward::WaveRewardPlugin, game_over_menu::GameOverMenuPlugin, high_scores::HighScoreMenuPlugin, controls_menu::ControlsMenuPlugin))
+        .add_plugins(navigation::NavigationPlugin)
         .add_systems(Update, (button_interactions, back_button_interactions));
     }
 }
 
 // Changes button colors when the user interacts with it
+#[allow(clippy::type_complexity)]
 pub fn button_interactions(
     mut button_query: Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<Button>)>
 ) {
@@ -0,0 +1,149 @@
+use bevy::prelude::*;
+use bevy::input::InputSystem;
+use bevy::ui::UiSystem;
+
+use crate::ui::{self, styles};
+use crate::game::{gamepad, keybinds, input::PlayerInputSystem};
+
+// Buttons can be selected with the gamepad d-pad and pressed with the confirm button
+// Only buttons in the top most menu can be selected, menus drawn over others have a higher global z index
+
+pub const OUTLINE_WIDTH: f32 = 4.0;
+
+#[derive(Resource, Default, Debug)]
+pub struct GamepadFocus {
+    pub button: Option<Entity>, // Button selected with the d-pad
+    pressed: Option<Entity>, // Button pressed with the confirm button last frame, it is released this frame
+}
+
+pub struct NavigationPlugin;
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GamepadFocus>()
+
+            // Runs after the ui has set button interactions, so the press isn't overwritten before gameplay and menus read it
+            .add_systems(PreUpdate, navigate_buttons.after(InputSystem).after(UiSystem::Focus).before(PlayerInputSystem))
+            .add_systems(Update, outline_focused_button);
+    }
+}
+
+fn navigate_buttons(
+    mut focus: ResMut<GamepadFocus>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut button_query: Query<(Entity, &GlobalTransform, &mut Interaction), With<ui::GenericButton>>,
+    node_query: Query<(Option<&Parent>, Option<&ZIndex>), With<Node>>,
+) {
+    if let Some(pressed) = focus.pressed.take() {
+        if let Ok((_, _, mut interaction)) = button_query.get_mut(pressed) {
+            if *interaction == Interaction::Pressed {
+                *interaction = Interaction::None;
+            }
+        }
+    }
+
+    // Buttons in the top most menu, with their position on the screen
+    let buttons: Vec<(Entity, Vec2, i32)> = button_query.iter()
+        .map(|(entity, transform, _)| (entity, transform.translation().truncate(), root_z_index(entity, &node_query)))
+        .collect();
+    let top_z_index = buttons.iter().map(|(_, _, z_index)| *z_index).max();
+    let buttons: Vec<(Entity, Vec2)> = buttons.into_iter()
+        .filter(|(_, _, z_index)| Some(*z_index) == top_z_index)
+        .map(|(entity, position, _)| (entity, position))
+        .collect();
+
+    // The selected button may have been despawned, or covered by another menu
+    if focus.button.is_some_and(|button| !buttons.iter().any(|(entity, _)| *entity == button)) {
+        focus.button = None;
+    }
+
+    let directions = [
+        (GamepadButtonType::DPadUp, Vec2::NEG_Y), // Ui positions start at the top of the screen
+        (GamepadButtonType::DPadDown, Vec2::Y),
+        (GamepadButtonType::DPadLeft, Vec2::NEG_X),
+        (GamepadButtonType::DPadRight, Vec2::X),
+    ];
+    let direction = directions.iter()
+        .find(|(button_type, _)| gamepad::just_pressed(&gamepads, &gamepad_buttons, *button_type))
+        .map(|(_, direction)| *direction);
+
+    if let Some(direction) = direction {
+        focus.button = match focus.button.and_then(|button| buttons.iter().find(|(entity, _)| *entity == button)) {
+            Some((_, position)) => next_button(*position, direction, &buttons).or(focus.button),
+
+            // Start from the top left button
+            None => buttons.iter()
+                .min_by(|(_, a), (_, b)| (a.y, a.x).partial_cmp(&(b.y, b.x)).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(entity, _)| *entity),
+        };
+    }
+
+    if gamepad::just_pressed(&gamepads, &gamepad_buttons, keybinds::GAMEPAD_CONFIRM) {
+        if let Some(button) = focus.button {
+            if let Ok((_, _, mut interaction)) = button_query.get_mut(button) {
+                *interaction = Interaction::Pressed;
+                focus.pressed = Some(button);
+            }
+        }
+    }
+}
+
+// Global z index of the menu a ui node is in
+fn root_z_index(entity: Entity, node_query: &Query<(Option<&Parent>, Option<&ZIndex>), With<Node>>) -> i32 {
+    let mut entity = entity;
+    while let Ok((parent, z_index)) = node_query.get(entity) {
+        if let Some(ZIndex::Global(z_index)) = z_index {
+            return *z_index;
+        }
+
+        match parent {
+            Some(parent) => entity = parent.get(),
+            None => break,
+        }
+    }
+    0
+}
+
+// Closest button in a direction, buttons that are further off to the side count as further away
+fn next_button(position: Vec2, direction: Vec2, buttons: &[(Entity, Vec2)]) -> Option<Entity> {
+    buttons.iter()
+        .filter_map(|(entity, button_position)| {
+            let offset = *button_position - position;
+            let along = offset.dot(direction);
+            let across = offset.perp_dot(direction).abs();
+
+            if along <= 0.0 {
+                return None;
+            }
+            Some((*entity, along + across * 2.0))
+        })
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(entity, _)| entity)
+}
+
+// Outlines the selected button, buttons keep a transparent outline so it isn't added and removed every time the selection changes
+fn outline_focused_button(
+    mut commands: Commands,
+    focus: Res<GamepadFocus>,
+    mut button_query: Query<(Entity, Option<&mut Outline>), With<ui::GenericButton>>,
+) {
+    for (entity, outline) in button_query.iter_mut() {
+        let color = match focus.button == Some(entity) {
+            true => Color::hex(styles::TEXT_BOLD_HEX).unwrap(),
+            false => Color::NONE,
+        };
+
+        match outline {
+            Some(mut outline) => {
+                if outline.color != color {
+                    outline.color = color;
+                }
+            },
+            None => {
+                commands.entity(entity).insert(Outline::new(Val::Px(OUTLINE_WIDTH), Val::ZERO, color));
+            },
+        }
+    }
+}
@@ -29,6 +29,7 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
                     ..default()
                 },
                 background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                z_index: ZIndex::Global(styles::MENU_Z_INDEX),
                 ..default()
             }
         )
@@ -70,6 +71,20 @@ pub fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>)
             );
         });
 
+        // Controls button
+        parent.spawn(
+            (
+                ui::GenericButton,
+                ui::controls_menu::layout::ControlsButton,
+                helpers::button(styles::BUTTON_STYLE),
+            )   
+        )
+        .with_children(|parent| {
+            parent.spawn(
+                helpers::text("Controls", styles::BUTTON_STYLE.text_style.unwrap(), &asset_server)
+            );
+        });
+
         // Back to main menu button
         parent.spawn(
             (
@@ -1,15 +1,25 @@
 use bevy::prelude::*;
 
-pub const FONT_PATH: &'static str = "fonts/Roboto-Medium.ttf";
+pub const FONT_PATH: &str = "fonts/Roboto-Medium.ttf";
 
-pub const BACKGROUND_HEX: &'static str = "1a0923";
-pub const FOREGROUND_HEX: &'static str = "33293e";
-pub const TEXT_HEX: &'static str = "ffffff";
-pub const TEXT_BOLD_HEX: &'static str = "cfeb73";
+pub const BACKGROUND_HEX: &str = "1a0923";
+pub const FOREGROUND_HEX: &str = "33293e";
+pub const TEXT_HEX: &str = "ffffff";
+pub const TEXT_BOLD_HEX: &str = "cfeb73";
+pub const TEXT_DISABLED_HEX: &str = "7d7387";
+pub const BOSS_HEALTH_HEX: &str = "c4303b";
 
-pub const BUTTON_DEFAULT_HEX: &'static str = "33293e";
-pub const BUTTON_HOVER_HEX: &'static str = "2c2336";
-pub const BUTTON_PRESSED_HEX: &'static str = "282030";
+pub const BUTTON_DEFAULT_HEX: &str = "33293e";
+pub const BUTTON_HOVER_HEX: &str = "2c2336";
+pub const BUTTON_PRESSED_HEX: &str = "282030";
+
+// Global z index of menus that are drawn over others, only buttons in the top most menu can be selected with a gamepad
+pub const REWARD_Z_INDEX: i32 = 1;
+pub const MENU_Z_INDEX: i32 = 2; // Pause and game over menus
+pub const CONTROLS_Z_INDEX: i32 = 3;
+pub const CURSOR_Z_INDEX: i32 = 4; // Gamepad cursor
+
+pub const GAMEPAD_CURSOR_SIZE: f32 = 16.0;
 
 pub struct TextStyle {
     pub size: f32,
@@ -45,6 +55,20 @@ pub const BODY_TEXT_STYLE: TextStyle = TextStyle {
     justify: JustifyText::Center,
 };
 
+pub const HINT_TEXT_STYLE: TextStyle = TextStyle {
+    size: 24.0,
+    color_hex: TEXT_HEX,
+    font: FONT_PATH,
+    justify: JustifyText::Left,
+};
+
+pub const REWARD_DESCRIPTION_TEXT_STYLE: TextStyle = TextStyle {
+    size: 32.0,
+    color_hex: TEXT_HEX,
+    font: FONT_PATH,
+    justify: JustifyText::Center,
+};
+
 // Ui Button style
 pub const BUTTON_STYLE: ButtonStyle = ButtonStyle {
     justify_content: JustifyContent::Center,
@@ -58,6 +82,45 @@ pub const BUTTON_STYLE: ButtonStyle = ButtonStyle {
     text_style: Some(BODY_TEXT_STYLE),
 };
 
+// Controls menu button style, the reset and back buttons are next to each other
+pub const CONTROLS_BUTTON_STYLE: ButtonStyle = ButtonStyle {
+    justify_content: JustifyContent::Center,
+    align_items: AlignItems::Center,
+
+    width: Val::Percent(20.0),
+    height: Val::Percent(100.0),
+
+    color_hex: BUTTON_DEFAULT_HEX,
+
+    text_style: Some(BODY_TEXT_STYLE),
+};
+
+// Button showing the key bound to an action in the controls menu
+pub const BINDING_BUTTON_STYLE: ButtonStyle = ButtonStyle {
+    justify_content: JustifyContent::Center,
+    align_items: AlignItems::Center,
+
+    width: Val::Percent(35.0),
+    height: Val::Percent(100.0),
+
+    color_hex: BUTTON_DEFAULT_HEX,
+
+    text_style: Some(REWARD_DESCRIPTION_TEXT_STYLE),
+};
+
+// Summon bar slot style
+pub const SUMMON_SLOT_STYLE: ButtonStyle = ButtonStyle {
+    justify_content: JustifyContent::Center,
+    align_items: AlignItems::Center,
+
+    width: Val::Percent(12.0),
+    height: Val::Percent(100.0),
+
+    color_hex: BUTTON_DEFAULT_HEX,
+
+    text_style: Some(HINT_TEXT_STYLE),
+};
+
 // Reward Button style
 pub const REWARD_BUTTON_STYLE: ButtonStyle = ButtonStyle {
     justify_content: JustifyContent::Center,
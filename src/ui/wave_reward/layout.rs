@@ -0,0 +1,123 @@
+use bevy::prelude::*;
+
+use crate::ui;
+use crate::game::{characters, keybinds, rewards};
+use ui::{helpers, styles};
+
+#[derive(Component)]
+pub struct WaveReward;
+
+pub fn spawn_wave_reward(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    reward_offer: Res<rewards::RewardOffer>,
+    summon_types: Res<characters::SummonTypes>,
+    collectible_types: Res<characters::CollectibleTypes>,
+    keybinds: Res<keybinds::Keybinds>,
+) {
+    commands.spawn(
+
+        (
+            WaveReward,
+
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.5).into(),
+                z_index: ZIndex::Global(styles::REWARD_Z_INDEX),
+                ..default()
+            }
+        )
+    )
+
+    .with_children(|parent| {
+
+        // Title
+        parent.spawn(
+            helpers::text("Choose a Reward", styles::TITLE_TEXT_STYLE, &asset_server)
+        );
+
+        // Row containing the reward cards
+        parent.spawn(
+            NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Row,
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(80.0),
+
+                    column_gap: Val::Percent(5.0),
+
+                    ..default()
+                },
+                ..default()
+            }
+        ).with_children(|parent| {
+            for (i, reward) in reward_offer.0.iter().enumerate() {
+                let (title, description) = reward_text(reward, &summon_types, &collectible_types);
+
+                // Reward card
+                parent.spawn(
+                    (
+                        ui::GenericButton,
+                        rewards::RewardCard(i),
+                        helpers::button(styles::REWARD_BUTTON_STYLE),
+                    )
+                )
+                .with_children(|parent| {
+                    parent.spawn(
+                        helpers::text(&title, styles::REWARD_BUTTON_STYLE.text_style.unwrap(), &asset_server)
+                    );
+                    parent.spawn(
+                        helpers::text(&description, styles::REWARD_DESCRIPTION_TEXT_STYLE, &asset_server)
+                    );
+
+                    // Show which key picks the card
+                    if let Some(binding) = keybinds.0.get(&keybinds::Action::ChooseReward(i)) {
+                        parent.spawn(
+                            helpers::text(&format!("[{}]", binding), styles::REWARD_DESCRIPTION_TEXT_STYLE, &asset_server)
+                        );
+                    }
+                });
+            }
+        });
+    });
+}
+
+// Title and description of a reward card
+fn reward_text(reward: &rewards::Reward, summon_types: &characters::SummonTypes, collectible_types: &characters::CollectibleTypes) -> (String, String) {
+    match *reward {
+        rewards::Reward::Collectibles { collectible_type, ammount } => (
+            format!("{} Cache", collectible_types.0[collectible_type].name),
+            format!("Gain {} {}", ammount, collectible_types.0[collectible_type].name),
+        ),
+        rewards::Reward::MaxHealth => (
+            "Vitality".to_string(),
+            format!("+{} max health", rewards::REWARD_MAX_HEALTH),
+        ),
+        rewards::Reward::FasterSummons => (
+            "Frenzy".to_string(),
+            format!("Summons shoot {}% faster", ((1.0 / rewards::REWARD_FIRING_RATE_MULTIPLIER - 1.0) * 100.0).round()),
+        ),
+        rewards::Reward::FreeSummon { summon_type } => (
+            "Free Summon".to_string(),
+            format!("Summon a {}", summon_types.0[summon_type].name),
+        ),
+    }
+}
+
+pub fn despawn_wave_reward(mut commands: Commands, wave_reward_query: Query<Entity, With<WaveReward>>) {
+    if let Ok(wave_reward_entity) = wave_reward_query.get_single() {
+        commands.entity(wave_reward_entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,15 @@
+use bevy::prelude::*;
+use crate::game::WaveState;
+
+pub mod layout;
+
+// Reward cards are picked through game::input, so the choice is recorded in replays
+pub struct WaveRewardPlugin;
+
+impl Plugin for WaveRewardPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(WaveState::Reward), layout::spawn_wave_reward)
+            .add_systems(OnExit(WaveState::Reward), layout::despawn_wave_reward);
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::prelude::*;
+
+use hexen_havoc::headless::{HeadlessGame, HEADLESS_ARENA_SIZE};
+use hexen_havoc::game::{gamepad::{self, GamepadCursor}, input::PlayerInput, keybinds};
+use hexen_havoc::ui::{navigation::GamepadFocus, GenericButton};
+
+const GAMEPAD: Gamepad = Gamepad { id: 0 };
+
+fn start_game() -> HeadlessGame {
+    let mut headless_game = HeadlessGame::default();
+    headless_game.start_seeded(11, true);
+    headless_game.connect_gamepad(GAMEPAD);
+    headless_game.update();
+    headless_game
+}
+
+fn player_input(headless_game: &HeadlessGame) -> PlayerInput {
+    *headless_game.app.world.resource::<PlayerInput>()
+}
+
+// Pushes the stick and returns the input it gave
+fn push_left_stick(headless_game: &mut HeadlessGame, position: Vec2) -> PlayerInput {
+    headless_game.set_gamepad_axis(GAMEPAD, GamepadAxisType::LeftStickX, position.x);
+    headless_game.set_gamepad_axis(GAMEPAD, GamepadAxisType::LeftStickY, position.y);
+    headless_game.update();
+    player_input(headless_game)
+}
+
+// Presses and releases a gamepad button, returns the interaction of every button on the frame it was pressed
+fn tap(headless_game: &mut HeadlessGame, button_type: GamepadButtonType, buttons: &[Entity]) -> Vec<Interaction> {
+    headless_game.set_gamepad_button(GAMEPAD, button_type, 1.0);
+    headless_game.update();
+    let interactions = buttons.iter().map(|button| *headless_game.app.world.get::<Interaction>(*button).unwrap()).collect();
+
+    headless_game.set_gamepad_button(GAMEPAD, button_type, 0.0);
+    headless_game.update();
+    interactions
+}
+
+#[test]
+fn left_stick_moves_the_player() {
+    let mut headless_game = start_game();
+
+    let input = push_left_stick(&mut headless_game, Vec2::new(gamepad::STICK_DEADZONE * 0.5, 0.0));
+    assert!(!input.up && !input.down && !input.left && !input.right);
+
+    let input = push_left_stick(&mut headless_game, Vec2::new(0.9, 0.0));
+    assert!(input.right && !input.left && !input.up && !input.down);
+
+    let input = push_left_stick(&mut headless_game, Vec2::new(-0.7, 0.7));
+    assert!(input.left && input.up && !input.right && !input.down);
+
+    // Diagonals only count on the axes pushed past the deadzone
+    let input = push_left_stick(&mut headless_game, Vec2::new(0.2, -0.9));
+    assert!(input.down && !input.left && !input.right && !input.up);
+
+    let input = push_left_stick(&mut headless_game, Vec2::ZERO);
+    assert!(!input.up && !input.down && !input.left && !input.right);
+}
+
+#[test]
+fn right_stick_moves_the_cursor() {
+    let mut headless_game = start_game();
+    headless_game.set_cursor_position(None);
+
+    // Inside the deadzone the mouse cursor is still used
+    headless_game.set_gamepad_axis(GAMEPAD, GamepadAxisType::RightStickX, gamepad::CURSOR_DEADZONE * 0.5);
+    headless_game.update();
+    assert!(!headless_game.app.world.resource::<GamepadCursor>().active);
+    assert_eq!(player_input(&headless_game).cursor_position, None);
+
+    // Starts from the middle of the screen when there is no mouse cursor
+    headless_game.set_gamepad_axis(GAMEPAD, GamepadAxisType::RightStickX, 1.0);
+    headless_game.update_for(10);
+    let gamepad_cursor = headless_game.app.world.resource::<GamepadCursor>();
+    assert!(gamepad_cursor.active);
+    assert!(gamepad_cursor.position.x > HEADLESS_ARENA_SIZE.x / 2.0);
+    assert_eq!(gamepad_cursor.position.y, HEADLESS_ARENA_SIZE.y / 2.0);
+    assert_eq!(player_input(&headless_game).cursor_position, Some(gamepad_cursor.position));
+
+    // Stays inside the arena
+    headless_game.update_for_seconds(5.0);
+    assert_eq!(headless_game.app.world.resource::<GamepadCursor>().position.x, HEADLESS_ARENA_SIZE.x);
+
+    // Letting go of the stick leaves the cursor where it is
+    headless_game.set_gamepad_axis(GAMEPAD, GamepadAxisType::RightStickX, 0.0);
+    headless_game.update();
+    let position = headless_game.app.world.resource::<GamepadCursor>().position;
+    headless_game.update_for(10);
+    assert_eq!(headless_game.app.world.resource::<GamepadCursor>().position, position);
+
+    // Moving the mouse switches back to it
+    headless_game.set_cursor_position(Some(Vec2::new(100.0, 100.0)));
+    headless_game.update();
+    assert!(!headless_game.app.world.resource::<GamepadCursor>().active);
+    assert_eq!(player_input(&headless_game).cursor_position, Some(Vec3::new(100.0, 100.0, 0.0)));
+}
+
+#[test]
+fn dpad_and_confirm_press_the_focused_button() {
+    let mut headless_game = start_game();
+
+    // Ui positions start at the top of the screen
+    let mut spawn_button = |position: Vec2| {
+        headless_game.app.world.spawn((GenericButton, Node::default(), Interaction::None, GlobalTransform::from_translation(position.extend(0.0)))).id()
+    };
+    let top_left = spawn_button(Vec2::new(100.0, 100.0));
+    let bottom_left = spawn_button(Vec2::new(100.0, 300.0));
+    let top_right = spawn_button(Vec2::new(400.0, 100.0));
+    let buttons = [top_left, bottom_left, top_right];
+    headless_game.update();
+
+    // Nothing is focused until the d-pad is used, then the top left button is
+    assert_eq!(tap(&mut headless_game, keybinds::GAMEPAD_CONFIRM, &buttons), vec![Interaction::None; 3]);
+    tap(&mut headless_game, GamepadButtonType::DPadDown, &buttons);
+    assert_eq!(headless_game.app.world.resource::<GamepadFocus>().button, Some(top_left));
+
+    tap(&mut headless_game, GamepadButtonType::DPadDown, &buttons);
+    assert_eq!(headless_game.app.world.resource::<GamepadFocus>().button, Some(bottom_left));
+
+    // There is nothing further down
+    tap(&mut headless_game, GamepadButtonType::DPadDown, &buttons);
+    assert_eq!(headless_game.app.world.resource::<GamepadFocus>().button, Some(bottom_left));
+
+    assert_eq!(tap(&mut headless_game, keybinds::GAMEPAD_CONFIRM, &buttons), vec![Interaction::None, Interaction::Pressed, Interaction::None]);
+    assert_eq!(*headless_game.app.world.get::<Interaction>(bottom_left).unwrap(), Interaction::None); // Released on the next frame
+
+    tap(&mut headless_game, GamepadButtonType::DPadUp, &buttons);
+    tap(&mut headless_game, GamepadButtonType::DPadRight, &buttons);
+    assert_eq!(tap(&mut headless_game, keybinds::GAMEPAD_CONFIRM, &buttons), vec![Interaction::None, Interaction::None, Interaction::Pressed]);
+}
@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use hexen_havoc::headless::HeadlessGame;
+use hexen_havoc::game::{difficulty_settings, enemies, score, GameState, TickRate, WaveState, WAVE_COUNTDOWN_SECONDS};
+
+const SEED: u64 = 1;
+
+// Starts a seeded game where summons are bought automatically
+fn start_game() -> HeadlessGame {
+    let mut headless_game = HeadlessGame::default();
+    headless_game.start_seeded(SEED, true);
+    headless_game
+}
+
+fn enemies(headless_game: &mut HeadlessGame) -> usize {
+    headless_game.app.world.query_filtered::<(), With<enemies::Enemy>>().iter(&headless_game.app.world).count()
+}
+
+#[test]
+fn first_wave_starts_after_the_countdown() {
+    let mut headless_game = start_game();
+
+    assert_eq!(headless_game.wave_state(), WaveState::Start);
+    let difficulty = headless_game.difficulty().unwrap();
+    assert_eq!(difficulty.wave, 0);
+    assert_eq!(difficulty.enemies, difficulty_settings::STARTING_ENEMIES);
+    assert_eq!(enemies(&mut headless_game), 0);
+
+    let player = headless_game.player().unwrap();
+    assert_eq!(player.health, player.max_health);
+    assert!(player.sentries.is_empty());
+
+    headless_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 + 1.5);
+    assert_eq!(headless_game.wave_state(), WaveState::Fight);
+    assert_eq!(headless_game.game_state(), GameState::Running);
+
+    // The first scripted wave spawns it's first group as soon as the wave starts, the second group is delayed
+    headless_game.update_for(10);
+    assert_eq!(enemies(&mut headless_game), 5);
+    assert_eq!(headless_game.difficulty().unwrap().wave, 0);
+
+    let player = headless_game.player().unwrap();
+    assert!(player.health > 0);
+}
+
+#[test]
+fn pause_key_pauses_the_game() {
+    let mut headless_game = start_game();
+
+    headless_game.press_key(KeyCode::Escape);
+    headless_game.update_for(2);
+    headless_game.release_key(KeyCode::Escape);
+    headless_game.update();
+    assert_eq!(headless_game.game_state(), GameState::Paused);
+
+    headless_game.press_key(KeyCode::Escape);
+    headless_game.update_for(2);
+    assert_eq!(headless_game.game_state(), GameState::Running);
+}
+
+#[test]
+fn same_seed_spawns_the_same_wave() {
+    let positions = |headless_game: &mut HeadlessGame| -> Vec<Vec3> {
+        headless_game.app.world.query_filtered::<&Transform, With<enemies::Enemy>>().iter(&headless_game.app.world).map(|transform| transform.translation).collect()
+    };
+
+    let mut first_game = start_game();
+    let mut second_game = start_game();
+    first_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 + 2.0);
+    second_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 + 2.0);
+
+    let mut first_positions = positions(&mut first_game);
+    let mut second_positions = positions(&mut second_game);
+    first_positions.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    second_positions.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+
+    assert!(!first_positions.is_empty());
+    assert_eq!(first_positions, second_positions);
+}
+
+#[test]
+fn wave_is_cleared_once() {
+    let mut headless_game = start_game();
+
+    // Several fixed steps run every update
+    headless_game.app.world.insert_resource(TickRate(600.0));
+    headless_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 + 1.5);
+    assert_eq!(headless_game.wave_state(), WaveState::Fight);
+
+    headless_game.app.world.resource_mut::<enemies::SpawnQueue>().spawns.clear();
+    let enemies: Vec<Entity> = headless_game.app.world.query_filtered::<Entity, With<enemies::Enemy>>().iter(&headless_game.app.world).collect();
+    for enemy in enemies {
+        headless_game.app.world.despawn(enemy);
+    }
+    headless_game.update_for(2);
+
+    assert_eq!(headless_game.wave_state(), WaveState::Reward);
+    assert_eq!(headless_game.app.world.resource::<score::Score>().wave_points, score::WAVE_POINTS);
+}
+
+#[test]
+fn score_only_changes_with_points() {
+    #[derive(Resource, Default)]
+    struct ScoreChanges(u32);
+
+    let mut headless_game = start_game();
+    headless_game.app
+        .init_resource::<ScoreChanges>()
+        .add_systems(Update, (|mut score_changes: ResMut<ScoreChanges>| score_changes.0 += 1).run_if(resource_changed::<score::Score>));
+    headless_game.update();
+    headless_game.app.world.resource_mut::<ScoreChanges>().0 = 0;
+
+    // Nothing gives points during the countdown
+    headless_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 / 2.0);
+    assert_eq!(headless_game.app.world.resource::<ScoreChanges>().0, 0);
+}
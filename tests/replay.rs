@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+
+use hexen_havoc::AppState;
+use hexen_havoc::headless::HeadlessGame;
+use hexen_havoc::game::{characters::Character, enemies, interpolation::Interpolated, score::Score, WAVE_COUNTDOWN_SECONDS};
+use hexen_havoc::game::replay::Replay;
+
+const SEED: u64 = 7;
+
+// Everything that should come out the same when a run is played again
+#[derive(Debug, PartialEq)]
+struct EndState {
+    wave: u32,
+    player_health: i32,
+    collectibles: Vec<i32>,
+    score: u64,
+    enemies: Vec<(Vec3, i32)>, // Simulated positions, the drawn positions depend on how far into a fixed step the frame ended
+    characters: usize,
+}
+
+fn end_state(headless_game: &mut HeadlessGame) -> EndState {
+    let world = &mut headless_game.app.world;
+    let mut enemies: Vec<(Vec3, i32)> = world.query_filtered::<(&Interpolated, &Character), With<enemies::Enemy>>()
+        .iter(world)
+        .map(|(interpolated, enemy)| (interpolated.translation(), enemy.health))
+        .collect();
+    enemies.sort_by(|a, b| a.0.x.total_cmp(&b.0.x).then(a.0.y.total_cmp(&b.0.y)));
+
+    let player = headless_game.player().unwrap();
+    let (player_health, collectibles) = (player.health, player.collectibles.clone());
+    EndState {
+        wave: headless_game.difficulty().unwrap().wave,
+        player_health,
+        collectibles,
+        score: headless_game.app.world.resource::<Score>().total(),
+        enemies,
+        characters: headless_game.characters().len(),
+    }
+}
+
+// Records a seeded run where the player walks around while the summons fight, and returns it's end state
+fn record_run(headless_game: &mut HeadlessGame) -> (Replay, EndState) {
+    headless_game.set_recording(true);
+    headless_game.start_seeded(SEED, true);
+
+    headless_game.update_for_seconds(WAVE_COUNTDOWN_SECONDS as f32 + 1.0);
+    for key in [KeyCode::KeyW, KeyCode::KeyD, KeyCode::KeyS, KeyCode::KeyA] {
+        headless_game.press_key(key);
+        headless_game.update_for_seconds(1.5);
+        headless_game.release_key(key);
+        headless_game.update_for_seconds(0.5);
+    }
+
+    let state = end_state(headless_game);
+    headless_game.set_app_state(AppState::GameCleanup);
+    headless_game.update();
+    headless_game.set_app_state(AppState::MainMenu);
+    headless_game.update();
+
+    (headless_game.take_recording().unwrap(), state)
+}
+
+#[test]
+fn replay_gives_the_same_run() {
+    let mut headless_game = HeadlessGame::default();
+    let (replay, recorded_state) = record_run(&mut headless_game);
+    assert!(!recorded_state.enemies.is_empty());
+
+    // Survives being saved and loaded
+    let replay = Replay::from_bytes(&replay.to_bytes().unwrap()).unwrap();
+    assert_eq!(replay.seed, SEED);
+
+    assert_eq!(headless_game.play_replay(replay), None);
+    assert_eq!(end_state(&mut headless_game), recorded_state);
+}
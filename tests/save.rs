@@ -0,0 +1,33 @@
+use hexen_havoc::AppState;
+use hexen_havoc::headless::HeadlessGame;
+use hexen_havoc::game::{high_scores::RunRecord, player::Player, save::SavedRun, WaveState};
+
+#[test]
+fn continued_run_counts_summons_once() {
+    let mut headless_game = HeadlessGame::default();
+    headless_game.start_seeded(5, true);
+
+    let mut player = headless_game.app.world.query::<&mut Player>().single_mut(&mut headless_game.app.world);
+    player.collectibles.iter_mut().for_each(|collectibles| *collectibles = 20);
+    headless_game.update_for(2);
+
+    // The run is saved when the next wave's countdown starts
+    headless_game.set_wave_state(WaveState::Reward);
+    headless_game.update();
+    headless_game.set_wave_state(WaveState::Start);
+    headless_game.update();
+
+    let saved_summons = headless_game.app.world.resource::<SavedRun>().save.as_ref().unwrap().summons_used.clone();
+    assert!(saved_summons.iter().sum::<u32>() > 0);
+
+    headless_game.set_app_state(AppState::GameCleanup);
+    headless_game.update();
+    headless_game.set_app_state(AppState::MainMenu);
+    headless_game.update();
+
+    headless_game.continue_game();
+    headless_game.update_for(2);
+
+    assert!(!headless_game.characters().is_empty());
+    assert_eq!(headless_game.app.world.resource::<RunRecord>().summons, saved_summons);
+}
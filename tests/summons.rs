@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use hexen_havoc::headless::HeadlessGame;
+use hexen_havoc::game::{characters::{SummonType, SummonTypes}, input::PlayerInput, interpolation::Interpolated, player::Player, summons, WaveState};
+
+// Starts a game where summons are bought from the summon bar, with enough collectibles for anything
+fn start_game() -> HeadlessGame {
+    let mut headless_game = HeadlessGame::default();
+    headless_game.start_seeded(3, false);
+
+    let mut player = headless_game.app.world.query::<&mut Player>().single_mut(&mut headless_game.app.world);
+    player.collectibles.iter_mut().for_each(|collectibles| *collectibles = 100);
+    headless_game
+}
+
+fn sentry_slot(headless_game: &HeadlessGame) -> usize {
+    headless_game.app.world.resource::<SummonTypes>().0.iter().position(|summon_info| summon_info.summon_type == Some(SummonType::Sentry)).unwrap()
+}
+
+fn sentries(headless_game: &mut HeadlessGame) -> Vec<Vec3> {
+    headless_game.app.world.query_filtered::<&Interpolated, With<summons::Sentry>>().iter(&headless_game.app.world).map(|interpolated| interpolated.translation()).collect()
+}
+
+fn buy(headless_game: &mut HeadlessGame, slot: usize) {
+    let key = [KeyCode::Digit1, KeyCode::Digit2, KeyCode::Digit3, KeyCode::Digit4, KeyCode::Digit5, KeyCode::Digit6][slot];
+    headless_game.press_key(key);
+    headless_game.update_for(2);
+    headless_game.release_key(key);
+    headless_game.update();
+}
+
+#[test]
+fn sentry_is_placed_at_the_cursor() {
+    let mut headless_game = start_game();
+    assert_eq!(headless_game.wave_state(), WaveState::Start);
+
+    let cursor_position = Vec2::new(700.0, 300.0);
+    let slot = sentry_slot(&headless_game);
+    headless_game.set_cursor_position(Some(cursor_position));
+    buy(&mut headless_game, slot);
+
+    assert_eq!(sentries(&mut headless_game), vec![cursor_position.extend(0.0)]);
+    assert!(headless_game.player().unwrap().sentries.is_empty());
+}
+
+#[test]
+fn sentry_waits_for_the_cursor() {
+    let mut headless_game = start_game();
+    let slot = sentry_slot(&headless_game);
+
+    headless_game.set_cursor_position(None);
+    buy(&mut headless_game, slot);
+
+    assert!(sentries(&mut headless_game).is_empty());
+    assert_eq!(headless_game.player().unwrap().sentries, vec![slot]);
+
+    // Placed once the player points somewhere
+    let cursor_position = Vec2::new(400.0, 500.0);
+    headless_game.set_cursor_position(Some(cursor_position));
+    headless_game.press_key(KeyCode::KeyQ);
+    headless_game.update_for(2);
+
+    assert_eq!(sentries(&mut headless_game), vec![cursor_position.extend(0.0)]);
+    assert!(headless_game.player().unwrap().sentries.is_empty());
+}
+
+#[test]
+fn clicking_the_summon_bar_doesnt_move_summons() {
+    let mut headless_game = start_game();
+
+    headless_game.press_mouse_button(MouseButton::Left);
+    headless_game.update();
+    assert!(headless_game.app.world.resource::<PlayerInput>().move_ranged_summons);
+    headless_game.release_mouse_button(MouseButton::Left);
+    headless_game.update();
+
+    // The ui isn't running, so the slot is hovered by hand
+    let slot = headless_game.app.world.spawn((Button, Node::default(), Interaction::Hovered, summons::SummonSlot(0))).id();
+    headless_game.press_mouse_button(MouseButton::Left);
+    headless_game.update();
+    assert!(!headless_game.app.world.resource::<PlayerInput>().move_ranged_summons);
+
+    headless_game.app.world.entity_mut(slot).insert(Interaction::None);
+    headless_game.update();
+    assert!(headless_game.app.world.resource::<PlayerInput>().move_ranged_summons);
+}